@@ -0,0 +1,127 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use toml::Value;
+
+use crate::config::{
+    error::ConfigError,
+    layer::{ConfigLayer, ConfigSource},
+};
+
+/// Git Commit with Conventional Commit messages enforced.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Overrides the case strategy
+    #[arg(long, global = true, value_name = "STRATEGY")]
+    pub case_strategy: Option<String>,
+    /// Overrides the editor used to write the description
+    #[arg(long, global = true)]
+    pub editor: Option<String>,
+    /// Overrides any config value, e.g. `-c case_strategy=uppercase`
+    #[arg(
+        short = 'c',
+        long = "config",
+        global = true,
+        value_name = "KEY=VALUE"
+    )]
+    pub overrides: Vec<String>,
+    /// Amends the last commit, keeping the trailers added by other tools
+    #[arg(long, global = true)]
+    pub amend: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Creates a `.gcmt.toml` at the root of the repository
+    Init {
+        /// Overwrites the existing config file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Prints a commit as gcmt reads it
+    Show {
+        /// The commit to show
+        #[arg(default_value = "HEAD")]
+        rev: String,
+        /// Prints the commit as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Commits without the prompts, checking the commit against the config
+    Commit {
+        /// The JSON file to read the commit from, or `-` for stdin
+        #[arg(long, value_name = "FILE")]
+        from_json: PathBuf,
+    },
+    /// Wraps the description of a commit message as gcmt wraps its own
+    Wrap {
+        /// The message file to wrap in place, such as the one given to a
+        /// `commit-msg` hook; stdin is wrapped to stdout when omitted
+        file: Option<PathBuf>,
+    },
+    /// Inspects the configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Prints the effective configuration
+    Show {
+        /// Prints the layer each value came from
+        #[arg(long)]
+        origin: bool,
+    },
+    /// Checks the config files and the config overrides for problems
+    Validate,
+    /// Creates a `.gcmt.toml` from a commitlint config
+    ImportCommitlint {
+        /// The commitlint config file, found at the root of the repository
+        /// when omitted
+        file: Option<PathBuf>,
+        /// Prints the config instead of writing it
+        #[arg(long)]
+        print: bool,
+        /// Overwrites the existing config file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+impl Cli {
+    /// The config layers for the command-line flags, in the order in which
+    /// they take precedence.
+    pub fn config_layers(&self) -> Result<Vec<ConfigLayer>, ConfigError> {
+        let mut layers = Vec::new();
+        if let Some(strategy) = &self.case_strategy {
+            layers.push(ConfigLayer::from_value(
+                ConfigSource::Cli("--case-strategy".to_string()),
+                "case_strategy",
+                Value::String(strategy.clone()),
+            )?);
+        }
+        if let Some(editor) = &self.editor {
+            layers.push(ConfigLayer::from_value(
+                ConfigSource::Cli("--editor".to_string()),
+                "editor",
+                Value::String(editor.clone()),
+            )?);
+        }
+        for pair in &self.overrides {
+            let source = ConfigSource::Cli(format!("-c {}", pair));
+            let Some((key, raw)) = pair.split_once('=') else {
+                return Err(ConfigError::InvalidValueError(
+                    source,
+                    "expected KEY=VALUE".to_string(),
+                ));
+            };
+            layers.push(ConfigLayer::from_pair(source, key.trim(), raw)?);
+        }
+        Ok(layers)
+    }
+}
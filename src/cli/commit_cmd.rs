@@ -0,0 +1,208 @@
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use console::style;
+
+use crate::{
+    commit::{self, commit::Commit, data::CommitData},
+    config::config::Config,
+    git::git::Git,
+};
+
+/// Prints the commit at `rev` as gcmt reads it with the template of the
+/// config, or as JSON when `json` is set. Returns the exit code of the
+/// command.
+pub fn show(rev: &str, json: bool, config: &Config) -> i32 {
+    let Some(message) = Git::message(rev) else {
+        eprintln!("{}", style(format!("Cannot find the commit {}", rev)).red());
+        return 1;
+    };
+    let commit = match Commit::parse_with(&message, &config.template) {
+        Ok(commit) => commit,
+        Err(error) => {
+            eprintln!("{}", style(format!("{}:{}", rev, error)).red());
+            return 1;
+        }
+    };
+    if !json {
+        println!("{}", config.template.render(&commit));
+        return 0;
+    }
+    match serde_json::to_string_pretty(&commit) {
+        Ok(content) => {
+            println!("{}", content);
+            0
+        }
+        Err(error) => {
+            eprintln!("{}", style(error).red());
+            1
+        }
+    }
+}
+
+/// Commits the commit read from a JSON file, or from stdin when `path` is
+/// `-`, once it passes the same checks as the answers to the prompts.
+/// Returns the exit code of the command.
+pub fn commit_from_json(path: &Path, config: &Config, amend: bool) -> i32 {
+    let content = if path == Path::new("-") {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content).map(|_| content)
+    } else {
+        fs::read_to_string(path)
+    };
+    let result = content
+        .map_err(|error| error.to_string())
+        .and_then(|content| {
+            serde_json::from_str::<CommitData>(&content)
+                .map_err(|error| error.to_string())
+        })
+        .and_then(|data| {
+            config
+                .builder()
+                .fill(&data, &config.types)
+                .and_then(|builder| builder.build())
+                .map_err(|error| error.to_string())
+        })
+        .and_then(|commit| {
+            let message = config.template.render(&commit);
+            if amend {
+                Git::amend(&message)
+            } else {
+                Git::commit(&message)
+            }
+        });
+    match result {
+        Ok(output) => {
+            print!("{}", style(output).green());
+            0
+        }
+        Err(error) => {
+            eprintln!("{}", style(error).red());
+            1
+        }
+    }
+}
+
+/// Wraps the description of the commit message in the file at `path`, in
+/// place, or of the one read from stdin to stdout when there is no `path`,
+/// to the width that applies to its commit type. The header is kept as it
+/// is. Returns the exit code of the command.
+pub fn wrap(path: Option<&Path>, config: &Config) -> i32 {
+    let content = match path {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content).map(|_| content)
+        }
+    };
+    let result = content.and_then(|message| {
+        let message = wrap_message(&message, config);
+        match path {
+            Some(path) => fs::write(path, message),
+            None => io::stdout().write_all(message.as_bytes()),
+        }
+    });
+    match result {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("{}", style(error).red());
+            1
+        }
+    }
+}
+
+/// The line below which git leaves the diff of `git commit --verbose`.
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+fn wrap_message(message: &str, config: &Config) -> String {
+    // the diff below the scissors and the comments that git adds after the
+    // message are kept as they are
+    let scissors = message
+        .match_indices(SCISSORS)
+        .map(|(i, _)| i)
+        .find(|i| *i == 0 || message[..*i].ends_with('\n'))
+        .unwrap_or(message.len());
+    let (message, diff) = message.split_at(scissors);
+    let mut comments = message.len();
+    for line in message.split_inclusive('\n').rev() {
+        let text = line.trim_end_matches('\n');
+        if !text.is_empty() && !text.starts_with('#') {
+            break;
+        }
+        comments -= line.len();
+    }
+    let (message, comments) = message.split_at(comments);
+    let (header, description) =
+        message.split_once('\n').unwrap_or((message, ""));
+    let commit_type = Commit::parse_with(header, &config.template)
+        .ok()
+        .map(|commit| commit.commit_type.name);
+    let width = config
+        .limits
+        .wrap_width(config.wrap_width, commit_type.as_deref());
+    let description = description.trim_matches('\n');
+    if width == 0 || description.is_empty() {
+        return format!("{}{}{}", message, comments, diff);
+    }
+    format!(
+        "{}\n\n{}\n{}{}",
+        header,
+        commit::wrap::wrap(description, width, config.limits.unit),
+        comments,
+        diff
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::config::Config;
+
+    use super::wrap_message;
+
+    #[test]
+    fn keeps_the_comments_and_the_diff_of_git() {
+        let config = Config {
+            wrap_width: 30,
+            ..Config::default()
+        };
+        let diff = "# ------------------------ >8 ------------------------\n\
+                    # Do not modify or remove the line above.\n\
+                    # Everything below it will be ignored.\n\
+                    diff --git a/src/main.rs b/src/main.rs\n\
+                    --- a/src/main.rs\n\
+                    +++ b/src/main.rs\n\
+                    @@ -1 +1 @@\n\
+                    -fn main() { println!(\"a rather long line of the diff\"); }\n\
+                    +fn main() { println!(\"another long line of the diff\"); }\n";
+        let comments = "\n\
+                        # Please enter the commit message for your changes. \
+                        Lines starting\n\
+                        # with '#' will be ignored, and an empty message \
+                        aborts the commit.\n\
+                        #\n\
+                        # On branch main\n\
+                        # Changes to be committed:\n\
+                        #\tmodified:   src/main.rs\n\
+                        #\n";
+        let message = format!(
+            "fix: keep the diff\n\n\
+             The description is long enough to be wrapped.\n{}{}",
+            comments, diff
+        );
+        assert_eq!(
+            wrap_message(&message, &config),
+            format!(
+                "fix: keep the diff\n\n\
+                 The description is long enough\n\
+                 to be wrapped.\n{}{}",
+                comments, diff
+            )
+        );
+        // a message without a description is left as it is
+        let message = format!("fix: keep the diff\n{}{}", comments, diff);
+        assert_eq!(wrap_message(&message, &config), message);
+    }
+}
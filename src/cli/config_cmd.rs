@@ -0,0 +1,205 @@
+use std::{fs, path::Path};
+
+use console::style;
+use toml::{Table, Value};
+
+use crate::{
+    config::{
+        commitlint::{read_commitlint, translate, COMMITLINT_FILES},
+        config::Config,
+        error::ConfigError,
+        extends::read_file,
+        layer::{lookup, ConfigLayer, ConfigSource, ResolvedConfig},
+        loader::CONFIG_FILE_NAME,
+        policy::{policy_files, read_policy},
+        validate::{validate_file, Diagnostic},
+    },
+    git::git::Git,
+};
+
+use super::{
+    args::Cli,
+    init_cmd::{target, write},
+};
+
+/// Prints the effective config. When `origin` is set, each value is preceded
+/// by a comment naming the layer it came from.
+pub fn show(resolved: &ResolvedConfig, origin: bool) {
+    if !origin {
+        match toml::to_string_pretty(&resolved.config) {
+            Ok(content) => print!("{}", content),
+            Err(error) => eprintln!("{}", error),
+        }
+        return;
+    }
+    for (path, source) in &resolved.origins {
+        let Some(value) = lookup(&resolved.values, path) else {
+            continue;
+        };
+        println!("# from {}", source);
+        match value {
+            Value::Array(items)
+                if !items.is_empty()
+                    && items.iter().all(|item| item.is_table()) =>
+            {
+                let mut table = Table::new();
+                table.insert(path.clone(), value.clone());
+                match toml::to_string_pretty(&table) {
+                    Ok(content) => print!("{}", content),
+                    Err(error) => eprintln!("{}", error),
+                }
+            }
+            _ => println!("{} = {}", path, value),
+        }
+        println!();
+    }
+}
+
+/// Validates every config file and config override, printing the problems
+/// found. Returns the exit code of the command.
+pub fn validate(cli: &Cli) -> i32 {
+    let mut diagnostics = Vec::new();
+    let mut errors = Vec::new();
+    let mut files = Config::files();
+    // the nested config files only apply to some of the changes, but they
+    // are checked wherever the command is run
+    if let Some(root) = Git::root() {
+        for file in Git::files(&root) {
+            let path = root.join(file);
+            let is_config = path.file_name() == Some(CONFIG_FILE_NAME.as_ref());
+            if is_config && !files.iter().any(|(other, _)| *other == path) {
+                files.push((path.clone(), ConfigSource::Repo(path)));
+            }
+        }
+    }
+    for (path, source) in files {
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(error) => {
+                errors.push(
+                    ConfigError::ReadError(path, error.to_string()).to_string(),
+                );
+                continue;
+            }
+        };
+        let file_diagnostics = validate_file(&content, &source);
+        if !file_diagnostics.is_empty() {
+            diagnostics.extend(file_diagnostics);
+            continue;
+        }
+        // the file itself is valid, but the files it extends may not be
+        match read_file(&path, &source) {
+            Ok(_) => {}
+            Err(ConfigError::InvalidConfigError(found)) => {
+                diagnostics.extend(found)
+            }
+            Err(error) => errors.push(error.to_string()),
+        }
+    }
+    let mut overrides = ConfigLayer::from_env_vars();
+    if let Err(error) = cli.config_layers() {
+        overrides.push(Err(error));
+    }
+    for error in overrides.into_iter().filter_map(Result::err) {
+        match error {
+            ConfigError::InvalidValueError(source, message) => diagnostics
+                .push(Diagnostic {
+                    source,
+                    position: None,
+                    message,
+                }),
+            error => errors.push(error.to_string()),
+        }
+    }
+    for path in policy_files(Git::root().as_deref()) {
+        match read_policy(&path) {
+            Ok(_) => {}
+            Err(ConfigError::InvalidConfigError(found)) => {
+                diagnostics.extend(found)
+            }
+            Err(error) => errors.push(error.to_string()),
+        }
+    }
+    // the locked keys can only be checked once every layer is valid
+    let mut warnings = Vec::new();
+    if diagnostics.is_empty() && errors.is_empty() {
+        match Config::load(cli.config_layers().unwrap_or_default()) {
+            Ok(resolved) => warnings = resolved.warnings,
+            Err(ConfigError::InvalidConfigError(found)) => {
+                diagnostics.extend(found)
+            }
+            Err(error) => errors.push(error.to_string()),
+        }
+    }
+    for warning in &warnings {
+        eprintln!("{}", style(warning).yellow());
+    }
+    for diagnostic in &diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+    for error in &errors {
+        eprintln!("{}", error);
+    }
+    if diagnostics.is_empty() && errors.is_empty() {
+        println!("The configuration is valid");
+        0
+    } else {
+        1
+    }
+}
+
+/// Translates a commitlint config into a `.gcmt.toml`, listing the rules
+/// that could not be translated. Returns the exit code of the command.
+pub fn import_commitlint(file: Option<&Path>, print: bool, force: bool) -> i32 {
+    let (root, path) = match target(force || print) {
+        Ok(target) => target,
+        Err(error) => {
+            eprintln!("{}", style(error).red());
+            return 1;
+        }
+    };
+    let file = file.map(Path::to_path_buf).or_else(|| {
+        COMMITLINT_FILES
+            .iter()
+            .map(|name| root.join(name))
+            .find(|path| path.is_file())
+    });
+    let Some(file) = file else {
+        eprintln!(
+            "{}",
+            style(format!("No commitlint config found in {}", root.display()))
+                .red()
+        );
+        return 1;
+    };
+    let import = match read_commitlint(&file) {
+        Ok(commitlint) => translate(&commitlint),
+        Err(error) => {
+            eprintln!("{}", style(error).red());
+            return 1;
+        }
+    };
+    for rule in &import.untranslated {
+        eprintln!("{}", style(format!("Not translated: {}", rule)).yellow());
+    }
+    let content = toml::to_string_pretty(&import.config).map(|content| {
+        format!(
+            "# Imported by `gcmt config import-commitlint` from {}\n\n{}",
+            file.file_name().unwrap_or_default().to_string_lossy(),
+            content
+        )
+    });
+    if !print {
+        return write(&path, content);
+    }
+    match content {
+        Ok(content) => {
+            print!("{}", content);
+            0
+        }
+        Err(error) => {
+            eprintln!("{}", style(error).red());
+            1
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    collections::BTreeSet,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+
+use crate::{
+    config::loader::CONFIG_FILE_NAME, git::git::Git, ui::init_ui::InitUI,
+};
+
+/// Writes a `.gcmt.toml` at the root of the repository from the answers to a
+/// few questions. An existing file is only replaced when `force` is set.
+/// Returns the exit code of the command.
+pub fn init(force: bool) -> i32 {
+    let (root, path) = match target(force) {
+        Ok(target) => target,
+        Err(error) => {
+            eprintln!("{}", style(error).red());
+            return 1;
+        }
+    };
+    let init =
+        InitUI::new(ColorfulTheme::default(), top_level_dirs(&root)).show();
+    write(&path, init.render())
+}
+
+/// The root of the repository, or the working directory outside of one,
+/// along with the path of its config file, which must not exist unless
+/// `force` is set.
+pub fn target(force: bool) -> Result<(PathBuf, PathBuf), String> {
+    let root = Git::root()
+        .or_else(|| env::current_dir().ok())
+        .ok_or("Cannot find the current directory")?;
+    let path = root.join(CONFIG_FILE_NAME);
+    if path.exists() && !force {
+        return Err(format!(
+            "{} already exists, use --force to overwrite it",
+            path.display()
+        ));
+    }
+    Ok((root, path))
+}
+
+/// Writes the rendered config file. Returns the exit code of the command.
+pub fn write(path: &Path, content: Result<String, toml::ser::Error>) -> i32 {
+    let result =
+        content
+            .map_err(|error| error.to_string())
+            .and_then(|content| {
+                fs::write(path, content).map_err(|error| error.to_string())
+            });
+    match result {
+        Ok(_) => {
+            println!(
+                "{}",
+                style(format!("Created {}", path.display())).green()
+            );
+            0
+        }
+        Err(error) => {
+            eprintln!("{}", style(error).red());
+            1
+        }
+    }
+}
+
+/// The top-level directories holding files that git does not ignore, or
+/// every visible directory when `root` is not in a repository.
+fn top_level_dirs(root: &Path) -> Vec<String> {
+    let files = Git::files(root);
+    if !files.is_empty() {
+        return files
+            .iter()
+            .filter_map(|file| file.split_once('/'))
+            .map(|(dir, _)| dir.to_string())
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect();
+    }
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut dirs = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.starts_with('.'))
+        .collect::<Vec<String>>();
+    dirs.sort();
+    dirs
+}
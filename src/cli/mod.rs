@@ -0,0 +1,4 @@
+pub mod args;
+pub mod commit_cmd;
+pub mod config_cmd;
+pub mod init_cmd;
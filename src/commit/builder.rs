@@ -1,19 +1,39 @@
+use serde::{Deserialize, Serialize};
+
 use super::{
     cmt_type::CommitType,
     commit::Commit,
-    constants::MAX_MESSAGE_LEN,
+    data::CommitData,
     error::{CasedComponent, CommitError},
-    strategy::CaseStrategy,
+    footer::{BreakingChangeStyle, Footer, FooterField, BREAKING_CHANGE_TOKEN},
+    limits::{split_footer, Limits},
+    scope::ScopeRegistry,
+    strategy::CaseStrategies,
+    template::MessageTemplate,
+    wrap::wrap,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// Builds a commit from the answers to the prompts, checking them against
+/// the config. It is serialized as the `CommitData` of its answers so far.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(into = "CommitData", from = "CommitData")]
 pub struct CommitBuilder {
     commit_type: Option<CommitType>,
     scope: Option<String>,
     subject: Option<String>,
     description: Option<String>,
     is_breaking_change: bool,
-    pub strategy: CaseStrategy,
+    footers: Vec<Footer>,
+    pub strategies: CaseStrategies,
+    pub limits: Limits,
+    pub scopes: ScopeRegistry,
+    /// The footers that the config defines
+    pub footer_fields: Vec<FooterField>,
+    pub breaking_change_style: BreakingChangeStyle,
+    /// The width to wrap the description to, or 0 to leave it as it is
+    pub wrap_width: usize,
+    /// The layout of the message
+    pub template: MessageTemplate,
 }
 
 impl CommitBuilder {
@@ -22,13 +42,20 @@ impl CommitBuilder {
         &mut self,
         commit_type: CommitType,
     ) -> Result<&mut Self, CommitError> {
+        if !self.strategies.commit_type.verify(&commit_type.name) {
+            return Err(CommitError::CaseError(
+                CasedComponent::Type,
+                commit_type.name,
+                self.strategies.commit_type,
+            ));
+        }
+        let allow_breaking = commit_type.allow_breaking;
+        let name = commit_type.name.clone();
         self.commit_type = Some(commit_type);
-        if self.message_len() + self.prefix_len() > MAX_MESSAGE_LEN {
-            return Err(CommitError::SubjectTooLongError {
-                available: MAX_MESSAGE_LEN - self.prefix_len(),
-                actual: self.message_len(),
-            });
+        if self.is_breaking_change && !allow_breaking {
+            return Err(CommitError::BreakingChangeNotAllowedError(name));
         }
+        self.check_header()?;
         Ok(self)
     }
 
@@ -37,65 +64,249 @@ impl CommitBuilder {
         &mut self,
         scope: impl AsRef<str>,
     ) -> Result<&mut Self, CommitError> {
-        let str_ref = scope.as_ref();
-        if !self.strategy.verify(str_ref) {
+        let scope = self.scopes.resolve(scope.as_ref())?;
+        if !self.strategies.scope.verify(&scope) {
             return Err(CommitError::CaseError(
                 CasedComponent::Scope,
-                str_ref.to_string(),
-                self.strategy,
+                scope,
+                self.strategies.scope,
             ));
         }
-        self.scope = Some(scope.as_ref().to_string());
-        if self.message_len() + self.prefix_len() > MAX_MESSAGE_LEN {
-            return Err(CommitError::SubjectTooLongError {
-                available: MAX_MESSAGE_LEN - self.prefix_len(),
-                actual: self.message_len(),
-            });
-        }
+        self.scope = Some(scope);
+        self.check_header()?;
         Ok(self)
     }
 
+    /// Removes the scope from the builder.
+    pub fn clear_scope(&mut self) -> &mut Self {
+        self.scope = None;
+        self
+    }
+
     /// Adds the subject to the builder.
     pub fn subject(
         &mut self,
         subject: impl AsRef<str>,
     ) -> Result<&mut Self, CommitError> {
         let str_ref = subject.as_ref();
-        if !self.strategy.verify(str_ref) {
+        if !self.strategies.subject.verify(str_ref) {
             return Err(CommitError::CaseError(
                 CasedComponent::Subject,
                 str_ref.to_string(),
-                self.strategy,
+                self.strategies.subject,
             ));
         }
-        if subject.as_ref().len() > MAX_MESSAGE_LEN - self.prefix_len() {
+        let limit = self.header_limit();
+        let length = self.measure(str_ref);
+        let header_len = self.header_len(str_ref);
+        if header_len > limit {
             return Err(CommitError::SubjectTooLongError {
-                available: MAX_MESSAGE_LEN - self.prefix_len(),
-                actual: subject.as_ref().len(),
+                available: limit.saturating_sub(header_len - length),
+                actual: length,
+                limit,
             });
         }
-        self.subject = Some(subject.as_ref().to_string());
+        self.subject = Some(str_ref.to_string());
         Ok(self)
     }
 
-    /// Adds the description to the builder.
-    pub fn description(&mut self, description: impl AsRef<str>) -> &mut Self {
+    /// Adds the description to the builder. It is wrapped to `wrap_width` or
+    /// to the body line limit of the commit type, whichever is lower, once
+    /// the commit is built, since the type may still change.
+    pub fn description(
+        &mut self,
+        description: impl AsRef<str>,
+    ) -> Result<&mut Self, CommitError> {
         self.description = Some(description.as_ref().to_string());
-        self
+        self.check_description()?;
+        Ok(self)
+    }
+
+    /// The description as it is committed with the selected commit type.
+    fn wrapped_description(&self) -> Option<String> {
+        let description = self.description.as_deref()?;
+        let width = self.limits.wrap_width(self.wrap_width, self.type_name());
+        Some(if width > 0 {
+            wrap(description, width, self.limits.unit)
+        } else {
+            description.to_string()
+        })
     }
 
     /// Marks the commit as a breaking change.
     pub fn breaking_change(&mut self) -> Result<&mut Self, CommitError> {
+        if let Some(commit_type) = &self.commit_type {
+            if !commit_type.allow_breaking {
+                return Err(CommitError::BreakingChangeNotAllowedError(
+                    commit_type.name.clone(),
+                ));
+            }
+        }
         self.is_breaking_change = true;
-        if self.message_len() + self.prefix_len() > MAX_MESSAGE_LEN {
-            return Err(CommitError::SubjectTooLongError {
-                available: MAX_MESSAGE_LEN - self.prefix_len(),
-                actual: self.message_len(),
-            });
+        self.check_header()?;
+        Ok(self)
+    }
+
+    /// Explains what broke and how to migrate, in the `BREAKING CHANGE`
+    /// footer. The commit is marked as a breaking change as well.
+    pub fn breaking_change_description(
+        &mut self,
+        description: impl AsRef<str>,
+    ) -> Result<&mut Self, CommitError> {
+        self.breaking_change()?;
+        let footer = Footer::new(BREAKING_CHANGE_TOKEN, description);
+        self.clear_breaking_change_description();
+        self.add_footer(footer)?;
+        // the explanation comes before the other footers
+        if let Some(footer) = self.footers.pop() {
+            self.footers.insert(0, footer);
+        }
+        Ok(self)
+    }
+
+    /// Marks the commit as a safe change.
+    pub fn clear_breaking_change(&mut self) -> &mut Self {
+        self.is_breaking_change = false;
+        self.clear_breaking_change_description();
+        self
+    }
+
+    fn clear_breaking_change_description(&mut self) {
+        self.footers.retain(|footer| !footer.is_breaking_change());
+    }
+
+    /// Adds a `Token: value` footer, as per `add_footer`.
+    pub fn footer(
+        &mut self,
+        token: impl AsRef<str>,
+        value: impl AsRef<str>,
+    ) -> Result<&mut Self, CommitError> {
+        self.add_footer(Footer::new(token, value))
+    }
+
+    /// Adds a footer. When the config defines the footer without allowing it
+    /// to be given several times, its previous value is replaced. The value
+    /// is checked against the rules of the footer in the config, if any.
+    pub fn add_footer(
+        &mut self,
+        footer: Footer,
+    ) -> Result<&mut Self, CommitError> {
+        if !Footer::is_valid_token(&footer.token) {
+            return Err(CommitError::InvalidFooterTokenError(footer.token));
+        }
+        let field = self.footer_field(&footer.token);
+        if let Some(field) = field {
+            field.check(&footer.value).map_err(|reason| {
+                CommitError::InvalidFooterError {
+                    token: footer.token.clone(),
+                    value: footer.value.clone(),
+                    reason,
+                }
+            })?;
         }
+        // the footers that the config does not define are never replaced,
+        // as git allows any trailer to be given several times
+        let multiple = field.is_none_or(|field| field.multiple);
+        if let Some(limit) = self.limits.footer_line(self.type_name()) {
+            let length = footer
+                .to_string()
+                .lines()
+                .map(|line| self.measure(line))
+                .max()
+                .unwrap_or(0);
+            if length > limit {
+                return Err(CommitError::FooterTooLongError {
+                    token: footer.token,
+                    length,
+                    limit,
+                });
+            }
+        }
+        if !multiple {
+            self.clear_footer(&footer.token);
+        }
+        self.footers.push(footer);
         Ok(self)
     }
 
+    /// The rules of the footer with the given token, if the config defines
+    /// it.
+    pub fn footer_field(&self, token: &str) -> Option<&FooterField> {
+        self.footer_fields.iter().find(|field| field.token == token)
+    }
+
+    /// Whether the commit has a footer with the given token.
+    pub fn has_footer(&self, token: &str) -> bool {
+        self.footers.iter().any(|footer| footer.token == token)
+    }
+
+    /// Removes every footer with the given token.
+    pub fn clear_footer(&mut self, token: impl AsRef<str>) -> &mut Self {
+        self.footers.retain(|footer| footer.token != token.as_ref());
+        self
+    }
+
+    /// The commit type selected so far, if any.
+    pub fn selected_type(&self) -> Option<&CommitType> {
+        self.commit_type.as_ref()
+    }
+
+    /// Sets the content of the commit through the same checks as the
+    /// prompts. The commit type is looked up by name in `types`.
+    pub fn fill(
+        &mut self,
+        data: &CommitData,
+        types: &[CommitType],
+    ) -> Result<&mut Self, CommitError> {
+        if let Some(name) = &data.commit_type {
+            let strategy = self.strategies.commit_type;
+            let mut commit_type = types
+                .iter()
+                .find(|commit_type| {
+                    commit_type.name == *name
+                        || strategy.apply(&commit_type.name) == *name
+                })
+                .cloned()
+                .ok_or_else(|| {
+                    CommitError::UnknownCommitTypeError(name.clone())
+                })?;
+            commit_type.name = strategy.apply(&commit_type.name);
+            self.commit_type(commit_type)?;
+        }
+        if let Some(scope) = &data.scope {
+            self.scope(scope)?;
+        }
+        if let Some(subject) = &data.subject {
+            self.subject(subject)?;
+        }
+        if let Some(body) = &data.body {
+            self.description(body)?;
+        }
+        if data.breaking {
+            self.breaking_change()?;
+        }
+        for footer in &data.footers {
+            if footer.is_breaking_change() {
+                self.breaking_change_description(&footer.value)?;
+            } else {
+                self.add_footer(footer.clone())?;
+            }
+        }
+        Ok(self)
+    }
+
+    /// The content of the commit so far.
+    pub fn data(&self) -> CommitData {
+        CommitData {
+            commit_type: self.type_name().map(String::from),
+            scope: self.scope.clone(),
+            subject: self.subject.clone(),
+            body: self.description.clone(),
+            breaking: self.is_breaking_change,
+            footers: self.footers.clone(),
+        }
+    }
+
     /// Builds the commit.
     pub fn build(&self) -> Result<Commit, CommitError> {
         let commit_type = self
@@ -106,41 +317,397 @@ impl CommitBuilder {
             .subject
             .clone()
             .ok_or(CommitError::MissingSubjectError)?;
-        if self.message_len() + self.prefix_len() > MAX_MESSAGE_LEN {
-            return Err(CommitError::SubjectTooLongError {
-                available: MAX_MESSAGE_LEN - self.prefix_len(),
-                actual: self.message_len(),
-            });
+        self.check_header()?;
+        self.check_description()?;
+        self.check_policy(&commit_type)?;
+        let mut footers = self.footers.clone();
+        if !self.breaking_change_style.has_footer() {
+            footers.retain(|footer| !footer.is_breaking_change());
         }
         Ok(Commit {
             commit_type,
             scope: self.scope.clone(),
             subject,
-            description: self.description.clone(),
-            is_breaking_change: self.is_breaking_change,
+            description: self.wrapped_description(),
+            is_breaking_change: self.has_bang(),
+            footers,
         })
     }
 
-    pub fn prefix_len(&self) -> usize {
-        let mut len: usize = 0;
-        match &self.commit_type {
-            Some(commit_type) => len += commit_type.len(),
-            None => len += 0,
-        }
-        match &self.scope {
-            Some(scope) => len += scope.len() + 2,
-            None => len += 0,
-        }
-        if self.is_breaking_change {
-            len += 1;
-        }
-        len
+    /// The header as it is rendered from the answers so far.
+    pub fn header(&self) -> String {
+        self.render_header(self.subject.as_deref().unwrap_or_default())
+    }
+
+    /// The length of the header with the given subject.
+    pub fn header_len(&self, subject: &str) -> usize {
+        self.measure(&self.render_header(subject))
+    }
+
+    fn render_header(&self, subject: &str) -> String {
+        let commit = Commit {
+            commit_type: self.commit_type.clone().unwrap_or_default(),
+            scope: self.scope.clone(),
+            subject: subject.to_string(),
+            description: None,
+            is_breaking_change: self.has_bang(),
+            footers: self.footers.clone(),
+        };
+        self.template.header(&commit)
+    }
+
+    /// The length of a line, in the unit of the limits.
+    pub fn measure(&self, line: &str) -> usize {
+        self.limits.unit.measure(line)
+    }
+
+    /// Whether the header has a `!`.
+    fn has_bang(&self) -> bool {
+        self.is_breaking_change && self.breaking_change_style.has_bang()
     }
 
+    /// The length of the subject.
     pub fn message_len(&self) -> usize {
         match &self.subject {
-            Some(subject) => subject.len(),
+            Some(subject) => self.measure(subject),
             None => 0,
         }
     }
+
+    /// The header limit for the selected commit type.
+    pub fn header_limit(&self) -> usize {
+        self.limits.header(self.type_name())
+    }
+
+    fn type_name(&self) -> Option<&str> {
+        self.commit_type.as_ref().map(|t| t.name.as_str())
+    }
+
+    fn check_header(&self) -> Result<(), CommitError> {
+        let limit = self.header_limit();
+        let header_len = self.measure(&self.header());
+        if header_len > limit {
+            return Err(CommitError::SubjectTooLongError {
+                available: limit
+                    .saturating_sub(header_len - self.message_len()),
+                actual: self.message_len(),
+                limit,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_policy(
+        &self,
+        commit_type: &CommitType,
+    ) -> Result<(), CommitError> {
+        if commit_type.requires_scope && self.scope.is_none() {
+            return Err(CommitError::MissingScopeError(
+                commit_type.name.clone(),
+            ));
+        }
+        let has_body = self
+            .description
+            .as_ref()
+            .is_some_and(|description| !description.trim().is_empty());
+        if commit_type.requires_body && !has_body {
+            return Err(CommitError::MissingBodyError(
+                commit_type.name.clone(),
+            ));
+        }
+        if self.is_breaking_change && !commit_type.allow_breaking {
+            return Err(CommitError::BreakingChangeNotAllowedError(
+                commit_type.name.clone(),
+            ));
+        }
+        let has_description =
+            self.footers.iter().any(Footer::is_breaking_change);
+        if self.is_breaking_change
+            && self.breaking_change_style.has_footer()
+            && !has_description
+        {
+            return Err(CommitError::MissingBreakingChangeError);
+        }
+        let required = self
+            .footer_fields
+            .iter()
+            .filter(|field| field.is_required(Some(&commit_type.name)));
+        for field in required {
+            if !self
+                .footers
+                .iter()
+                .any(|footer| footer.token == field.token)
+            {
+                return Err(CommitError::MissingFooterError(
+                    field.token.clone(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_description(&self) -> Result<(), CommitError> {
+        let Some(description) = self.wrapped_description() else {
+            return Ok(());
+        };
+        let (body, footer) = split_footer(&description);
+        // the description starts on the third line of the message, after the
+        // header and a blank line
+        let first_line = 3;
+        if let Some(limit) = self.limits.body_line(self.type_name()) {
+            for (i, line) in body.iter().enumerate() {
+                let length = self.measure(line);
+                if length > limit {
+                    return Err(CommitError::BodyLineTooLongError {
+                        line: first_line + i,
+                        length,
+                        limit,
+                    });
+                }
+            }
+        }
+        if let Some(limit) = self.limits.footer_line(self.type_name()) {
+            for (i, line) in footer.iter().enumerate() {
+                let length = self.measure(line);
+                if length > limit {
+                    return Err(CommitError::FooterLineTooLongError {
+                        line: first_line + body.len() + i,
+                        length,
+                        limit,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<CommitBuilder> for CommitData {
+    fn from(builder: CommitBuilder) -> Self {
+        builder.data()
+    }
+}
+
+impl From<CommitData> for CommitBuilder {
+    /// Takes the answers as they are, with the default config. Use `fill`
+    /// to check them against a config instead.
+    fn from(data: CommitData) -> Self {
+        Self {
+            commit_type: data.commit_type.map(|name| CommitType {
+                name,
+                ..CommitType::default()
+            }),
+            scope: data.scope,
+            subject: data.subject,
+            description: data.body,
+            is_breaking_change: data.breaking,
+            footers: data.footers,
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commit::{
+        cmt_type::CommitType,
+        error::CommitError,
+        footer::BreakingChangeStyle,
+        limits::{LengthUnit, LimitOverrides},
+        template::MessageTemplate,
+    };
+
+    use super::CommitBuilder;
+
+    fn render(style: BreakingChangeStyle) -> Result<String, CommitError> {
+        let mut builder = CommitBuilder {
+            breaking_change_style: style,
+            ..CommitBuilder::default()
+        };
+        builder
+            .commit_type(CommitType {
+                name: "feat".to_string(),
+                ..CommitType::default()
+            })?
+            .subject("drop the old flag")?
+            .footer("Refs", "GCMT-3")?
+            .breaking_change()?;
+        if style.has_footer() {
+            assert_eq!(
+                builder.build(),
+                Err(CommitError::MissingBreakingChangeError)
+            );
+            builder.breaking_change_description("use --new instead")?;
+        }
+        builder.build().map(|commit| commit.to_string())
+    }
+
+    #[test]
+    fn counts_the_rendered_header() {
+        let mut builder = CommitBuilder::default();
+        builder.limits.header = 16;
+        builder
+            .commit_type(CommitType {
+                name: "feat".to_string(),
+                ..CommitType::default()
+            })
+            .unwrap()
+            .scope("api")
+            .unwrap();
+        // `feat(api): ` takes 11 of the 16 characters
+        assert!(builder.subject("abcde").is_ok());
+        assert!(builder.subject("abcdef").is_err());
+        assert!(builder.subject("修复空输入").is_ok());
+        builder.limits.unit = LengthUnit::Columns;
+        assert!(matches!(
+            builder.subject("修复空输入"),
+            Err(CommitError::SubjectTooLongError {
+                available: 5,
+                actual: 10,
+                limit: 16,
+            })
+        ));
+        // `feat(api): ✨ ` takes 14 columns once the gitmoji is rendered
+        builder.template = MessageTemplate::new(
+            "{type}{(scope)}: {emoji }{subject}",
+            Vec::new(),
+        )
+        .unwrap();
+        builder
+            .subject("ab")
+            .unwrap()
+            .commit_type(
+                CommitType::new("feat", None::<&str>)
+                    .with_gitmoji("✨", ":sparkles:"),
+            )
+            .unwrap();
+        assert!(builder.subject("abc").is_err());
+    }
+
+    #[test]
+    fn enforces_type_policies() {
+        let fix = CommitType {
+            name: "fix".to_string(),
+            requires_scope: true,
+            requires_body: true,
+            allow_breaking: false,
+            ..CommitType::default()
+        };
+        let mut builder = CommitBuilder::default();
+        builder
+            .commit_type(fix.clone())
+            .unwrap()
+            .subject("trim")
+            .unwrap();
+        let error = builder.build().unwrap_err();
+        assert_eq!(error, CommitError::MissingScopeError("fix".to_string()));
+        assert_eq!(
+            error.to_string(),
+            "Commits of type 'fix' must have a scope"
+        );
+        builder.scope("cli").unwrap();
+        assert_eq!(
+            builder.build(),
+            Err(CommitError::MissingBodyError("fix".to_string()))
+        );
+        builder.description("  \n").unwrap();
+        assert_eq!(
+            builder.build(),
+            Err(CommitError::MissingBodyError("fix".to_string()))
+        );
+        builder.description("trim the input").unwrap();
+        assert!(builder.build().is_ok());
+        assert_eq!(
+            builder.breaking_change().err(),
+            Some(CommitError::BreakingChangeNotAllowedError(
+                "fix".to_string()
+            ))
+        );
+        // a type that forbids breaking changes cannot replace one that
+        // allows them once the commit is breaking
+        let mut builder = CommitBuilder::default();
+        builder
+            .commit_type(CommitType::new("feat", None::<&str>))
+            .unwrap()
+            .breaking_change()
+            .unwrap();
+        assert_eq!(
+            builder.commit_type(fix).err(),
+            Some(CommitError::BreakingChangeNotAllowedError(
+                "fix".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn wraps_the_description_for_the_final_type() {
+        let mut builder = CommitBuilder {
+            wrap_width: 20,
+            ..CommitBuilder::default()
+        };
+        builder.limits.types.insert(
+            "docs".to_string(),
+            LimitOverrides {
+                body_line: Some(10),
+                ..LimitOverrides::default()
+            },
+        );
+        builder
+            .subject("explain the flags")
+            .unwrap()
+            .description("the flags are listed in the readme")
+            .unwrap()
+            .commit_type(CommitType::new("docs", None::<&str>))
+            .unwrap();
+        assert_eq!(
+            builder.build().unwrap().description.as_deref(),
+            Some("the flags\nare listed\nin the\nreadme")
+        );
+    }
+
+    #[test]
+    fn numbers_long_lines_within_the_message() {
+        let mut builder = CommitBuilder {
+            wrap_width: 0,
+            ..CommitBuilder::default()
+        };
+        builder.limits.body_line = Some(10);
+        builder.limits.footer_line = Some(12);
+        assert_eq!(
+            builder.description("short\n\nthis line is too long").err(),
+            Some(CommitError::BodyLineTooLongError {
+                line: 5,
+                length: 21,
+                limit: 10,
+            })
+        );
+        assert_eq!(
+            builder
+                .description("short\n\nRefs: #1\nReviewed-by: someone")
+                .err(),
+            Some(CommitError::FooterLineTooLongError {
+                line: 6,
+                length: 20,
+                limit: 12,
+            })
+        );
+    }
+
+    #[test]
+    fn marks_breaking_changes() {
+        assert_eq!(
+            render(BreakingChangeStyle::Bang).unwrap(),
+            "feat!: drop the old flag\n\nRefs: GCMT-3"
+        );
+        assert_eq!(
+            render(BreakingChangeStyle::Footer).unwrap(),
+            "feat: drop the old flag\n\n\
+             BREAKING CHANGE: use --new instead\nRefs: GCMT-3"
+        );
+        assert_eq!(
+            render(BreakingChangeStyle::Both).unwrap(),
+            "feat!: drop the old flag\n\n\
+             BREAKING CHANGE: use --new instead\nRefs: GCMT-3"
+        );
+    }
 }
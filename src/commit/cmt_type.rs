@@ -1,19 +1,92 @@
 use std::fmt::Display;
 
-use serde::{Deserialize, Serialize};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+
+lazy_static! {
+    static ref SHORTCODE: Regex = Regex::new(r"^:[\w+-]+:$").unwrap();
+}
 
 /// Represents a type of commit.
-#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct CommitType {
     /// The name of the commit type
     pub name: String,
     /// The description of the commit type, to be shown in the terminal
     /// user interface
     pub description: Option<String>,
+    /// The gitmoji of the commit type, such as ✨
+    pub emoji: Option<String>,
+    /// The gitmoji shortcode of the commit type, such as `:sparkles:`
+    #[serde(default, deserialize_with = "shortcode")]
+    pub shortcode: Option<String>,
+    /// Whether commits of this type must have a scope
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub requires_scope: bool,
+    /// Whether commits of this type must have a description
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub requires_body: bool,
+    /// Whether commits of this type may be marked as breaking changes
+    #[serde(
+        default = "default_allow_breaking",
+        skip_serializing_if = "is_true"
+    )]
+    pub allow_breaking: bool,
+    /// The semantic versioning impact of commits of this type
+    #[serde(default, skip_serializing_if = "SemverImpact::is_none")]
+    pub semver_impact: SemverImpact,
+    /// Whether the type is accepted, but not offered in the type picker
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub hidden: bool,
+}
+
+/// The part of the semantic version that a commit causes to be bumped.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Default,
+    Deserialize,
+    Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SemverImpact {
+    #[default]
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl SemverImpact {
+    /// Whether commits do not cause a release.
+    pub fn is_none(&self) -> bool {
+        *self == SemverImpact::None
+    }
+}
+
+impl Display for SemverImpact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SemverImpact::None => write!(f, "none"),
+            SemverImpact::Patch => write!(f, "patch"),
+            SemverImpact::Minor => write!(f, "minor"),
+            SemverImpact::Major => write!(f, "major"),
+        }
+    }
 }
 
 impl Display for CommitType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(emoji) = &self.emoji {
+            write!(f, "{} ", emoji)?;
+        }
         write!(
             f,
             "{}: {}",
@@ -23,6 +96,22 @@ impl Display for CommitType {
     }
 }
 
+impl Default for CommitType {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            description: None,
+            emoji: None,
+            shortcode: None,
+            requires_scope: false,
+            requires_body: false,
+            allow_breaking: default_allow_breaking(),
+            semver_impact: SemverImpact::None,
+            hidden: false,
+        }
+    }
+}
+
 impl CommitType {
     pub fn new<T: AsRef<str>, K: AsRef<str>>(
         name: T,
@@ -30,11 +119,54 @@ impl CommitType {
     ) -> Self {
         let name = name.as_ref().to_string();
         let description = description.map(|d| d.as_ref().to_string());
-        Self { name, description }
+        Self {
+            name,
+            description,
+            ..Self::default()
+        }
     }
 
-    /// The length of the commit type name
-    pub fn len(&self) -> usize {
-        self.name.len()
+    /// Sets the semantic versioning impact of the commit type.
+    pub fn with_semver_impact(mut self, semver_impact: SemverImpact) -> Self {
+        self.semver_impact = semver_impact;
+        self
     }
+
+    /// Sets the gitmoji of the commit type, and its shortcode.
+    pub fn with_gitmoji(mut self, emoji: &str, shortcode: &str) -> Self {
+        self.emoji = Some(emoji.to_string());
+        self.shortcode = Some(shortcode.to_string());
+        self
+    }
+}
+
+/// Whether the text is a gitmoji shortcode, such as `:sparkles:`.
+pub fn is_shortcode(text: &str) -> bool {
+    SHORTCODE.is_match(text)
+}
+
+/// Reads a shortcode, which must be of the form `:name:`.
+fn shortcode<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    let shortcode = String::deserialize(deserializer)?;
+    if !is_shortcode(&shortcode) {
+        return Err(serde::de::Error::custom(format!(
+            "invalid shortcode `{}`, expected `:name:`",
+            shortcode
+        )));
+    }
+    Ok(Some(shortcode))
+}
+
+fn default_allow_breaking() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
 }
@@ -1,49 +1,59 @@
 use std::fmt::Display;
 
+use serde::{Deserialize, Serialize};
+
 use super::{
-    builder::CommitBuilder, cmt_type::CommitType, strategy::CaseStrategy,
+    builder::CommitBuilder,
+    cmt_type::{CommitType, SemverImpact},
+    data::CommitData,
+    footer::Footer,
+    strategy::CaseStrategies,
+    template::MessageTemplate,
 };
 
-/// Represents a commit.
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// Represents a commit. It is serialized as `CommitData`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(into = "CommitData", try_from = "CommitData")]
 pub struct Commit {
     pub commit_type: CommitType,
     pub scope: Option<String>,
     pub subject: String,
     pub description: Option<String>,
     pub is_breaking_change: bool,
+    pub footers: Vec<Footer>,
 }
 
 impl Commit {
     /// Creates a builder that can be used to build a new commit.
-    pub fn builder(strategy: CaseStrategy) -> CommitBuilder {
+    pub fn builder(strategies: CaseStrategies) -> CommitBuilder {
         let mut builder = CommitBuilder::default();
-        builder.strategy = strategy;
+        builder.strategies = strategies;
         builder
     }
+
+    /// Whether the commit is a breaking change, as marked by a `!` in the
+    /// header or by a `BREAKING CHANGE` footer.
+    pub fn is_breaking(&self) -> bool {
+        self.is_breaking_change
+            || self.footers.iter().any(Footer::is_breaking_change)
+    }
+
+    /// The part of the semantic version that this commit causes to be
+    /// bumped. Breaking changes always cause a major bump.
+    pub fn semver_impact(&self) -> SemverImpact {
+        if self.is_breaking() {
+            SemverImpact::Major
+        } else {
+            self.commit_type.semver_impact
+        }
+    }
 }
 
 impl Display for Commit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let scope = match &self.scope {
-            Some(scope) => format!("({})", scope),
-            None => String::new(),
-        };
-        let description = match &self.description {
-            Some(description) => {
-                if description.is_empty() {
-                    String::new()
-                } else {
-                    format!("\n\n{}", description)
-                }
-            }
-            None => String::new(),
-        };
-        let breaking = if self.is_breaking_change { "!" } else { "" };
-        let content = format!(
-            "{}{}{}: {}{}",
-            self.commit_type.name, scope, breaking, self.subject, description
-        );
+        // the template of the config is applied by rendering the commit
+        // with it instead
+        let content = MessageTemplate::default().render(self);
 
         // replace the unescaped " with \"
         // this probably would be better outside of the display impl, as it
@@ -1 +1,4 @@
 pub const MAX_MESSAGE_LEN: usize = 72;
+pub const DEFAULT_WRAP_WIDTH: usize = 72;
+pub const DEFAULT_HEADER_TEMPLATE: &str =
+    "{type}{(scope)}{breaking}: {subject}";
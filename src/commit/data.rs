@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    cmt_type::CommitType, commit::Commit, error::CommitError, footer::Footer,
+};
+
+/// The content of a commit, as it is read from or written to JSON, without
+/// the config that checks it. Every field is optional, so that an
+/// unfinished commit can be represented as well.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommitData {
+    /// The name of the commit type
+    #[serde(rename = "type")]
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    pub subject: Option<String>,
+    /// The description, without the footers
+    pub body: Option<String>,
+    /// Whether the header marks the commit as a breaking change with a `!`.
+    /// A `BREAKING CHANGE` footer is kept in the footers.
+    pub breaking: bool,
+    pub footers: Vec<Footer>,
+}
+
+impl From<Commit> for CommitData {
+    fn from(commit: Commit) -> Self {
+        Self {
+            breaking: commit.is_breaking_change,
+            commit_type: Some(commit.commit_type.name),
+            scope: commit.scope,
+            subject: Some(commit.subject),
+            body: commit.description,
+            footers: commit.footers,
+        }
+    }
+}
+
+impl TryFrom<CommitData> for Commit {
+    type Error = CommitError;
+
+    /// Only the name of the commit type is known from the data, so the
+    /// other properties of the type are left to their defaults.
+    fn try_from(data: CommitData) -> Result<Self, Self::Error> {
+        let name = data
+            .commit_type
+            .ok_or(CommitError::MissingCommitTypeError)?;
+        Ok(Self {
+            commit_type: CommitType {
+                name,
+                ..CommitType::default()
+            },
+            scope: data.scope,
+            subject: data.subject.ok_or(CommitError::MissingSubjectError)?,
+            description: data.body,
+            is_breaking_change: data.breaking,
+            footers: data.footers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commit::commit::Commit;
+
+    #[test]
+    fn converts_to_json() {
+        let commit = Commit::parse(
+            "fix(api)!: reject empty names\n\nNames were trimmed.\n\nRefs #12",
+        )
+        .unwrap();
+        let json = serde_json::to_string(&commit).unwrap();
+        assert_eq!(
+            json,
+            "{\"type\":\"fix\",\"scope\":\"api\",\
+             \"subject\":\"reject empty names\",\
+             \"body\":\"Names were trimmed.\",\"breaking\":true,\
+             \"footers\":[{\"token\":\"Refs\",\"separator\":\"hash\",\
+             \"value\":\"12\"}]}"
+        );
+        assert_eq!(serde_json::from_str::<Commit>(&json).unwrap(), commit);
+        assert!(serde_json::from_str::<Commit>("{\"type\":\"fix\"}").is_err());
+    }
+
+    #[test]
+    fn keeps_the_breaking_change_footer() {
+        let commit = Commit::parse("fix: x\n\nBREAKING CHANGE: y").unwrap();
+        let json = serde_json::to_string(&commit).unwrap();
+        assert_eq!(
+            json,
+            "{\"type\":\"fix\",\"scope\":null,\"subject\":\"x\",\
+             \"body\":null,\"breaking\":false,\
+             \"footers\":[{\"token\":\"BREAKING CHANGE\",\
+             \"separator\":\"colon\",\"value\":\"y\"}]}"
+        );
+        let parsed = serde_json::from_str::<Commit>(&json).unwrap();
+        assert_eq!(parsed, commit);
+        assert_eq!(parsed.to_string(), "fix: x\n\nBREAKING CHANGE: y");
+    }
+}
@@ -1,19 +1,81 @@
 use std::{error::Error, fmt::Display};
 
-use crate::commit::constants::MAX_MESSAGE_LEN;
-
 use super::strategy::CaseStrategy;
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CommitError {
-    SubjectTooLongError{available: usize, actual: usize},
+    SubjectTooLongError {
+        available: usize,
+        actual: usize,
+        limit: usize,
+    },
+    BodyLineTooLongError {
+        line: usize,
+        length: usize,
+        limit: usize,
+    },
+    FooterLineTooLongError {
+        line: usize,
+        length: usize,
+        limit: usize,
+    },
     MissingCommitTypeError,
+    UnknownCommitTypeError(String),
     MissingSubjectError,
     CaseError(CasedComponent, String, CaseStrategy),
+    MissingScopeError(String),
+    MissingBodyError(String),
+    BreakingChangeNotAllowedError(String),
+    MissingBreakingChangeError,
+    UnknownScopeError {
+        scope: String,
+        suggestion: Option<String>,
+    },
+    MissingFooterError(String),
+    InvalidFooterTokenError(String),
+    InvalidFooterError {
+        token: String,
+        value: String,
+        reason: String,
+    },
+    FooterTooLongError {
+        token: String,
+        length: usize,
+        limit: usize,
+    },
+}
+
+/// A problem in a commit message, at the given line and column, both
+/// starting at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    EmptyMessageError,
+    MissingCommitTypeError,
+    UnclosedScopeError,
+    EmptyScopeError,
+    MissingSeparatorError,
+    MissingSubjectError,
+    MissingBlankLineError,
+    /// The header does not follow the template of the config, which
+    /// expects the given part where it goes wrong
+    TemplateMismatchError {
+        expected: String,
+        template: String,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CasedComponent {
+    Type,
     Scope,
     Subject,
 }
@@ -21,30 +83,156 @@ pub enum CasedComponent {
 impl std::fmt::Display for CommitError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self {
-            CommitError::SubjectTooLongError{available, actual} => {
+            CommitError::SubjectTooLongError {
+                available,
+                actual,
+                limit,
+            } => {
                 write!(
-                    f, 
-                    "The subject is too long: {}, should be less than {} characters such that the length of the entire commit message is less than {} characters",
+                    f,
+                    "The subject is too long: {}, should be at most {} characters such that the header is at most {} characters",
                     actual,
                     available,
-                    MAX_MESSAGE_LEN
+                    limit
+                )
+            }
+            CommitError::BodyLineTooLongError {
+                line,
+                length,
+                limit,
+            } => {
+                write!(
+                    f,
+                    "Line {} of the message is {} characters long, which exceeds the body line limit of {} characters",
+                    line,
+                    length,
+                    limit
+                )
+            }
+            CommitError::FooterLineTooLongError {
+                line,
+                length,
+                limit,
+            } => {
+                write!(
+                    f,
+                    "Line {} of the message is {} characters long, which exceeds the footer line limit of {} characters",
+                    line,
+                    length,
+                    limit
                 )
             }
             CommitError::MissingCommitTypeError => {
                 write!(f, "You did not select a commit type")
             }
+            CommitError::UnknownCommitTypeError(commit_type) => {
+                write!(f, "The commit type '{}' is not allowed", commit_type)
+            }
             CommitError::MissingSubjectError => {
                 write!(f, "You did not enter a subject")
             }
             CommitError::CaseError(component, content, strategy) => {
                 write!(
-                    f, 
+                    f,
                     "The content '{}' for {} does not match the case strategy: {}",
                     content,
                     component,
                     strategy
                 )
             }
+            CommitError::MissingScopeError(commit_type) => {
+                write!(f, "Commits of type '{}' must have a scope", commit_type)
+            }
+            CommitError::MissingBodyError(commit_type) => {
+                write!(
+                    f,
+                    "Commits of type '{}' must have a description",
+                    commit_type
+                )
+            }
+            CommitError::BreakingChangeNotAllowedError(commit_type) => {
+                write!(
+                    f,
+                    "Commits of type '{}' cannot be breaking changes",
+                    commit_type
+                )
+            }
+            CommitError::MissingBreakingChangeError => {
+                write!(
+                    f,
+                    "Breaking changes must explain what broke in a 'BREAKING CHANGE' footer"
+                )
+            }
+            CommitError::UnknownScopeError { scope, suggestion } => {
+                write!(f, "The scope '{}' is not allowed", scope)?;
+                match suggestion {
+                    Some(suggestion) => {
+                        write!(f, ", did you mean '{}'?", suggestion)
+                    }
+                    None => Ok(()),
+                }
+            }
+            CommitError::MissingFooterError(token) => {
+                write!(f, "Commits must have a '{}' footer", token)
+            }
+            CommitError::InvalidFooterTokenError(token) => write!(
+                f,
+                "The footer token '{}' may only contain letters, digits, '_' and '-'",
+                token
+            ),
+            CommitError::InvalidFooterError {
+                token,
+                value,
+                reason,
+            } => write!(
+                f,
+                "The value '{}' of the '{}' footer {}",
+                value, token, reason
+            ),
+            CommitError::FooterTooLongError {
+                token,
+                length,
+                limit,
+            } => write!(
+                f,
+                "The '{}' footer is {} characters long, which exceeds the footer line limit of {} characters",
+                token, length, limit
+            ),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.kind)
+    }
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseErrorKind::EmptyMessageError => {
+                write!(f, "The commit message is empty")
+            }
+            ParseErrorKind::MissingCommitTypeError => {
+                write!(f, "Expected a commit type")
+            }
+            ParseErrorKind::UnclosedScopeError => {
+                write!(f, "The scope is missing its closing ')'")
+            }
+            ParseErrorKind::EmptyScopeError => write!(f, "The scope is empty"),
+            ParseErrorKind::MissingSeparatorError => {
+                write!(f, "Expected ': ' before the subject")
+            }
+            ParseErrorKind::MissingSubjectError => {
+                write!(f, "Expected a subject")
+            }
+            ParseErrorKind::MissingBlankLineError => {
+                write!(f, "Expected a blank line after the header")
+            }
+            ParseErrorKind::TemplateMismatchError { expected, template } => {
+                write!(f, "Expected {} as in `{}`", expected, template)
+            }
         }
     }
 }
@@ -52,6 +240,7 @@ impl std::fmt::Display for CommitError {
 impl Display for CasedComponent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            CasedComponent::Type => write!(f, "Type"),
             CasedComponent::Scope => write!(f, "Scope"),
             CasedComponent::Subject => write!(f, "Subject"),
         }
@@ -59,3 +248,5 @@ impl Display for CasedComponent {
 }
 
 impl Error for CommitError {}
+
+impl Error for ParseError {}
@@ -0,0 +1,285 @@
+use std::fmt::Display;
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The token of the footer describing a breaking change.
+pub const BREAKING_CHANGE_TOKEN: &str = "BREAKING CHANGE";
+
+/// How breaking changes are marked in the commit message.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakingChangeStyle {
+    /// A `!` after the type or scope
+    Bang,
+    /// A `BREAKING CHANGE` footer explaining what broke
+    Footer,
+    /// Both the `!` and the footer
+    #[default]
+    Both,
+}
+
+impl BreakingChangeStyle {
+    /// Whether the header of breaking changes has a `!`.
+    pub fn has_bang(&self) -> bool {
+        *self != BreakingChangeStyle::Footer
+    }
+
+    /// Whether breaking changes must explain what broke in a footer.
+    pub fn has_footer(&self) -> bool {
+        *self != BreakingChangeStyle::Bang
+    }
+}
+
+/// A trailer at the end of the commit message, such as `Refs: #123`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Footer {
+    /// The token of the trailer, e.g. `Refs`
+    pub token: String,
+    #[serde(default)]
+    pub separator: Separator,
+    /// The value of the trailer, which may span several lines
+    pub value: String,
+}
+
+/// What separates the token of a footer from its value.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Separator {
+    /// `Token: value`
+    #[default]
+    Colon,
+    /// `Token #value`
+    Hash,
+}
+
+impl Display for Separator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Separator::Colon => write!(f, ": "),
+            Separator::Hash => write!(f, " #"),
+        }
+    }
+}
+
+impl Display for Footer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.token, self.separator, self.value)
+    }
+}
+
+impl Footer {
+    pub fn new<T: AsRef<str>, K: AsRef<str>>(token: T, value: K) -> Self {
+        Self {
+            token: token.as_ref().to_string(),
+            separator: Separator::Colon,
+            value: value.as_ref().to_string(),
+        }
+    }
+
+    /// Reads a line of the form `Token: value` or `Token #value`, where the
+    /// token is valid as per `is_valid_token`.
+    pub fn parse(line: &str) -> Option<Self> {
+        let token_len = if line.starts_with("BREAKING CHANGE: ") {
+            BREAKING_CHANGE_TOKEN.len()
+        } else {
+            line.find(|c: char| !is_token_char(c)).unwrap_or(line.len())
+        };
+        if token_len == 0 {
+            return None;
+        }
+        let (token, rest) = line.split_at(token_len);
+        let (separator, value) = if let Some(value) = rest.strip_prefix(": ") {
+            (Separator::Colon, value)
+        } else if let Some(value) = rest.strip_prefix(" #") {
+            (Separator::Hash, value)
+        } else {
+            return None;
+        };
+        Some(Self {
+            token: token.to_string(),
+            separator,
+            value: value.to_string(),
+        })
+    }
+
+    /// Whether the token is made of word characters and hyphens, with
+    /// `BREAKING CHANGE` as the one token that may contain a space.
+    pub fn is_valid_token(token: &str) -> bool {
+        token == BREAKING_CHANGE_TOKEN
+            || (!token.is_empty() && token.chars().all(is_token_char))
+    }
+
+    /// Whether the footer describes a breaking change.
+    pub fn is_breaking_change(&self) -> bool {
+        self.token == BREAKING_CHANGE_TOKEN || self.token == "BREAKING-CHANGE"
+    }
+}
+
+/// A footer that the config defines, to be asked for in the prompt flow.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FooterField {
+    /// The token of the trailer, e.g. `Refs`
+    #[serde(deserialize_with = "token")]
+    pub token: String,
+    /// The description of the footer, shown as its prompt
+    pub description: Option<String>,
+    /// Whether every commit must have this footer
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub required: bool,
+    /// The commit types whose commits must have this footer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_for: Vec<String>,
+    /// Whether the footer can be given several times
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub multiple: bool,
+    /// A regex that the values must match, e.g. `^[A-Z]+-\d+$`
+    pub pattern: Option<Pattern>,
+    /// The values that are allowed, when the footer is one of a fixed set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+}
+
+impl FooterField {
+    /// Whether commits of the given type must have this footer.
+    pub fn is_required(&self, commit_type: Option<&str>) -> bool {
+        self.required
+            || commit_type.is_some_and(|commit_type| {
+                self.required_for.iter().any(|name| name == commit_type)
+            })
+    }
+
+    /// Checks a value against the pattern and the allowed values, returning
+    /// the reason when it is rejected.
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        if !self.values.is_empty() && !self.values.iter().any(|v| v == value) {
+            return Err(format!("must be one of {}", self.values.join(", ")));
+        }
+        if let Some(pattern) = &self.pattern {
+            if !pattern.0.is_match(value) {
+                return Err(format!("must match `{}`", pattern));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A regex that the values of a footer must match. It is compiled once,
+/// when the config is read.
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    /// Compiles the pattern, returning the problem when it is not a valid
+    /// regex.
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        Regex::new(pattern).map(Pattern).map_err(|error| {
+            // the syntax errors end with the problem, after a copy of the
+            // pattern pointing at it
+            let error = error.to_string();
+            let reason = error.lines().last().unwrap_or_default();
+            format!("invalid pattern: {}", reason.trim_start_matches("error: "))
+        })
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.as_str())
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for Pattern {}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Pattern::new(&pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Reads the token of a footer field, which must be valid as per
+/// `Footer::is_valid_token`.
+fn token<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    let token = String::deserialize(deserializer)?;
+    if !Footer::is_valid_token(&token) {
+        return Err(serde::de::Error::custom(format!(
+            "invalid footer token `{}`, expected letters, digits, `_` and `-`",
+            token
+        )));
+    }
+    Ok(token)
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Footer, FooterField, Pattern};
+
+    #[test]
+    fn checks_tokens() {
+        assert!(Footer::is_valid_token("Reviewed-by"));
+        assert!(Footer::is_valid_token("Change_Id"));
+        assert!(Footer::is_valid_token("BREAKING CHANGE"));
+        assert!(!Footer::is_valid_token("Reviewed by"));
+        assert!(!Footer::is_valid_token(""));
+        assert!(!Footer::is_valid_token("Refs:"));
+    }
+
+    #[test]
+    fn checks_values() {
+        let refs = FooterField {
+            token: "Refs".to_string(),
+            pattern: Some(Pattern::new(r"^[A-Z]+-\d+$").unwrap()),
+            required_for: vec!["fix".to_string()],
+            ..FooterField::default()
+        };
+        assert!(refs.check("GCMT-12").is_ok());
+        assert!(refs.check("#12").is_err());
+        assert!(refs.is_required(Some("fix")));
+        assert!(!refs.is_required(Some("feat")));
+        let risk = FooterField {
+            token: "Risk".to_string(),
+            values: vec!["low".to_string(), "high".to_string()],
+            ..FooterField::default()
+        };
+        assert!(risk.check("low").is_ok());
+        assert!(risk.check("medium").is_err());
+        assert_eq!(
+            Pattern::new("[a-").err().as_deref(),
+            Some("invalid pattern: unclosed character class")
+        );
+    }
+}
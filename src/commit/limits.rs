@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+use super::{constants::MAX_MESSAGE_LEN, footer::Footer};
+
+/// The length limits of the lines of a commit message.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Limits {
+    /// The maximum length of the header, i.e. the first line
+    pub header: usize,
+    /// The maximum length of each line of the body, if any
+    pub body_line: Option<usize>,
+    /// The maximum length of each footer line, if any
+    pub footer_line: Option<usize>,
+    /// Overrides of the limits for individual commit types, by type name
+    pub types: BTreeMap<String, LimitOverrides>,
+    /// How the length of the lines is measured
+    pub unit: LengthUnit,
+}
+
+/// How the length of a line is measured.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthUnit {
+    /// The number of characters, as commitlint counts them
+    #[default]
+    Chars,
+    /// The number of columns taken in a terminal, where CJK characters and
+    /// most emoji take two columns
+    Columns,
+}
+
+impl LengthUnit {
+    /// The length of a line.
+    pub fn measure(&self, line: &str) -> usize {
+        match self {
+            LengthUnit::Chars => line.chars().count(),
+            LengthUnit::Columns => line.width(),
+        }
+    }
+}
+
+/// Overrides of the limits for a single commit type.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitOverrides {
+    pub header: Option<usize>,
+    pub body_line: Option<usize>,
+    pub footer_line: Option<usize>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            header: MAX_MESSAGE_LEN,
+            body_line: None,
+            footer_line: None,
+            types: BTreeMap::new(),
+            unit: LengthUnit::default(),
+        }
+    }
+}
+
+impl Limits {
+    /// The header limit for the given commit type.
+    pub fn header(&self, commit_type: Option<&str>) -> usize {
+        self.overrides(commit_type)
+            .and_then(|overrides| overrides.header)
+            .unwrap_or(self.header)
+    }
+
+    /// The body line limit for the given commit type.
+    pub fn body_line(&self, commit_type: Option<&str>) -> Option<usize> {
+        self.overrides(commit_type)
+            .and_then(|overrides| overrides.body_line)
+            .or(self.body_line)
+    }
+
+    /// The footer line limit for the given commit type.
+    pub fn footer_line(&self, commit_type: Option<&str>) -> Option<usize> {
+        self.overrides(commit_type)
+            .and_then(|overrides| overrides.footer_line)
+            .or(self.footer_line)
+    }
+
+    /// The width to wrap the description of the given commit type to:
+    /// `wrap_width`, or the body line limit when it is lower. 0 leaves the
+    /// description as it is.
+    pub fn wrap_width(
+        &self,
+        wrap_width: usize,
+        commit_type: Option<&str>,
+    ) -> usize {
+        match self.body_line(commit_type) {
+            Some(limit) if wrap_width > 0 => limit.min(wrap_width),
+            _ => wrap_width,
+        }
+    }
+
+    fn overrides(&self, commit_type: Option<&str>) -> Option<&LimitOverrides> {
+        self.types.get(commit_type?)
+    }
+}
+
+/// Splits a description into its body lines and its footer lines. The
+/// footer is the last paragraph, when each of its lines is a git trailer
+/// such as `Refs: #123`, or the continuation of one.
+pub fn split_footer(description: &str) -> (Vec<&str>, Vec<&str>) {
+    let lines = description.trim_end().lines().collect::<Vec<&str>>();
+    let start = lines
+        .iter()
+        .rposition(|line| line.trim().is_empty())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let paragraph = &lines[start..];
+    let is_footer = paragraph
+        .first()
+        .is_some_and(|line| Footer::parse(line).is_some())
+        && paragraph.iter().all(|line| {
+            Footer::parse(line).is_some()
+                || line.starts_with(char::is_whitespace)
+        });
+    if is_footer {
+        (lines[..start].to_vec(), paragraph.to_vec())
+    } else {
+        (lines, Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_footer, LengthUnit, LimitOverrides, Limits};
+
+    #[test]
+    fn overrides_limits_per_type() {
+        let mut limits = Limits {
+            body_line: Some(100),
+            ..Limits::default()
+        };
+        limits.types.insert(
+            "docs".to_string(),
+            LimitOverrides {
+                header: Some(100),
+                footer_line: Some(80),
+                ..LimitOverrides::default()
+            },
+        );
+        assert_eq!(limits.header(None), 72);
+        assert_eq!(limits.header(Some("feat")), 72);
+        assert_eq!(limits.header(Some("docs")), 100);
+        assert_eq!(limits.body_line(Some("docs")), Some(100));
+        assert_eq!(limits.footer_line(Some("feat")), None);
+        assert_eq!(limits.footer_line(Some("docs")), Some(80));
+    }
+
+    #[test]
+    fn measures_chars_or_columns() {
+        assert_eq!(LengthUnit::Chars.measure("修复 bug"), 6);
+        assert_eq!(LengthUnit::Columns.measure("修复 bug"), 8);
+    }
+
+    #[test]
+    fn splits_the_trailing_footer() {
+        let (body, footer) =
+            split_footer("body\n\nRefs: #1\nReviewed-by: a\n  continued\n");
+        assert_eq!(body, ["body", ""]);
+        assert_eq!(footer, ["Refs: #1", "Reviewed-by: a", "  continued"]);
+        let (body, footer) = split_footer("body\n\nnot: a footer line\nprose");
+        assert_eq!(body, ["body", "", "not: a footer line", "prose"]);
+        assert!(footer.is_empty());
+        let (body, footer) = split_footer("Refs: #1");
+        assert!(body.is_empty());
+        assert_eq!(footer, ["Refs: #1"]);
+    }
+}
@@ -1,6 +1,14 @@
 pub mod builder;
 pub mod cmt_type;
+#[allow(clippy::module_inception)]
 pub mod commit;
 pub mod constants;
+pub mod data;
 pub mod error;
+pub mod footer;
+pub mod limits;
+pub mod parse;
+pub mod scope;
 pub mod strategy;
+pub mod template;
+pub mod wrap;
@@ -0,0 +1,229 @@
+use std::str::FromStr;
+
+use super::{
+    cmt_type::CommitType,
+    commit::Commit,
+    error::{ParseError, ParseErrorKind},
+    footer::Footer,
+    template::MessageTemplate,
+};
+
+impl Commit {
+    /// Reads a commit message following the Conventional Commits grammar:
+    /// a `type(scope)!: subject` header, then an optional body and footers,
+    /// each after a blank line. Rendering the commit gives back the message,
+    /// without its trailing whitespace.
+    ///
+    /// Only the name of the commit type is known from the message, so the
+    /// other properties of the type are left to their defaults.
+    pub fn parse(message: &str) -> Result<Self, ParseError> {
+        Self::parse_with(message, &MessageTemplate::default())
+    }
+
+    /// Reads a commit message whose header and trailers follow a template.
+    /// The trailers that the template adds are left out, as rendering the
+    /// commit adds them again, while the other footers that share their
+    /// tokens are kept.
+    pub fn parse_with(
+        message: &str,
+        template: &MessageTemplate,
+    ) -> Result<Self, ParseError> {
+        let message = message.trim_end();
+        let lines = message.lines().collect::<Vec<&str>>();
+        let Some(header) = lines.first() else {
+            return Err(error(1, 1, ParseErrorKind::EmptyMessageError));
+        };
+        let mut commit = template.parse_header(header)?;
+        match lines.get(1) {
+            None => return Ok(commit),
+            Some(line) if !line.is_empty() => {
+                return Err(error(2, 1, ParseErrorKind::MissingBlankLineError))
+            }
+            Some(_) => {}
+        }
+        let rest = &lines[2..];
+        // the footers are the last paragraph, when it starts with a trailer;
+        // the lines that are not trailers continue the value of the previous
+        // footer
+        let start = rest
+            .iter()
+            .rposition(|line| line.trim().is_empty())
+            .map_or(0, |i| i + 1);
+        let end = match rest.get(start).and_then(|line| Footer::parse(line)) {
+            Some(_) => {
+                for line in &rest[start..] {
+                    match (Footer::parse(line), commit.footers.last_mut()) {
+                        (Some(footer), _) => commit.footers.push(footer),
+                        (None, Some(footer)) => {
+                            footer.value.push('\n');
+                            footer.value.push_str(line);
+                        }
+                        (None, None) => {}
+                    }
+                }
+                start
+            }
+            None => rest.len(),
+        };
+        let body = rest[..end].join("\n");
+        let body = body.trim_end();
+        if !body.is_empty() {
+            commit.description = Some(body.to_string());
+        }
+        for trailer in template.added_trailers(&commit) {
+            let added =
+                commit.footers.iter().rposition(|footer| *footer == trailer);
+            if let Some(i) = added {
+                commit.footers.remove(i);
+            }
+        }
+        Ok(commit)
+    }
+}
+
+impl FromStr for Commit {
+    type Err = ParseError;
+
+    fn from_str(message: &str) -> Result<Self, Self::Err> {
+        Commit::parse(message)
+    }
+}
+
+/// Reads the `type(scope)!: subject` header.
+pub(super) fn parse_header(header: &str) -> Result<Commit, ParseError> {
+    let column = |offset: usize| header[..offset].chars().count() + 1;
+    let type_len = header
+        .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+        .unwrap_or(header.len());
+    if type_len == 0 {
+        return Err(error(1, 1, ParseErrorKind::MissingCommitTypeError));
+    }
+    let mut commit = Commit {
+        commit_type: CommitType {
+            name: header[..type_len].to_string(),
+            ..CommitType::default()
+        },
+        ..Commit::default()
+    };
+    let mut offset = type_len;
+    if header[offset..].starts_with('(') {
+        let Some(len) = header[offset..].find(')') else {
+            return Err(error(
+                1,
+                column(offset),
+                ParseErrorKind::UnclosedScopeError,
+            ));
+        };
+        let scope = &header[offset + 1..offset + len];
+        if scope.trim().is_empty() {
+            return Err(error(
+                1,
+                column(offset),
+                ParseErrorKind::EmptyScopeError,
+            ));
+        }
+        commit.scope = Some(scope.to_string());
+        offset += len + 1;
+    }
+    if header[offset..].starts_with('!') {
+        commit.is_breaking_change = true;
+        offset += 1;
+    }
+    if !header[offset..].starts_with(": ") {
+        return Err(error(
+            1,
+            column(offset),
+            ParseErrorKind::MissingSeparatorError,
+        ));
+    }
+    offset += 2;
+    if header[offset..].trim().is_empty() {
+        return Err(error(
+            1,
+            column(offset),
+            ParseErrorKind::MissingSubjectError,
+        ));
+    }
+    commit.subject = header[offset..].to_string();
+    Ok(commit)
+}
+
+fn error(line: usize, column: usize, kind: ParseErrorKind) -> ParseError {
+    ParseError { line, column, kind }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commit::{
+        commit::Commit,
+        error::ParseErrorKind,
+        footer::{Footer, Separator},
+    };
+
+    #[test]
+    fn parses_and_renders_messages() {
+        let messages = [
+            "feat: add a flag",
+            "fix(api)!: reject empty names",
+            "docs: explain scopes\n\nFirst paragraph.\n\nSecond paragraph.",
+            "fix: handle errors\n\nRefs #12\nReviewed-by: Ann",
+            "feat!: drop the old flag\n\nThe flag was unused.\n\n\
+             BREAKING CHANGE: the `--old` flag is gone,\n\
+             use `--new` instead.\nRefs: GCMT-3",
+        ];
+        for message in messages {
+            let commit = Commit::parse(message).unwrap();
+            assert_eq!(commit.to_string(), message);
+        }
+        let commit = Commit::parse(messages[4]).unwrap();
+        assert_eq!(commit.description.as_deref(), Some("The flag was unused."));
+        assert_eq!(commit.footers.len(), 2);
+        assert!(commit.footers[0].is_breaking_change());
+        assert_eq!(commit.footers[0].value.lines().count(), 2);
+        assert_eq!(
+            Commit::parse(messages[3]).unwrap().footers[0],
+            Footer {
+                token: "Refs".to_string(),
+                separator: Separator::Hash,
+                value: "12".to_string(),
+            }
+        );
+        assert!("chore: bump\n\nBREAKING-CHANGE: x"
+            .parse::<Commit>()
+            .unwrap()
+            .is_breaking());
+    }
+
+    #[test]
+    fn reports_positions() {
+        let position = |message: &str| {
+            let error = Commit::parse(message).unwrap_err();
+            (error.line, error.column, error.kind)
+        };
+        assert_eq!(position(""), (1, 1, ParseErrorKind::EmptyMessageError));
+        assert_eq!(
+            position(": x"),
+            (1, 1, ParseErrorKind::MissingCommitTypeError)
+        );
+        assert_eq!(
+            position("fix(api: x"),
+            (1, 4, ParseErrorKind::UnclosedScopeError)
+        );
+        assert_eq!(
+            position("fix(): x"),
+            (1, 4, ParseErrorKind::EmptyScopeError)
+        );
+        assert_eq!(
+            position("Merge branch"),
+            (1, 6, ParseErrorKind::MissingSeparatorError)
+        );
+        assert_eq!(
+            position("fix(é)!: \n\nbody"),
+            (1, 10, ParseErrorKind::MissingSubjectError)
+        );
+        assert_eq!(
+            position("fix: x\nbody"),
+            (2, 1, ParseErrorKind::MissingBlankLineError)
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use std::fmt::Display;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::error::CommitError;
+
+/// Represents a scope that can be selected for a commit.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scope {
+    /// The name of the scope
+    pub name: String,
+    /// The description of the scope, to be shown in the terminal user
+    /// interface
+    pub description: Option<String>,
+    /// Other names that resolve to this scope
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Globs of the paths that belong to this scope, relative to the root
+    /// of the repository, e.g. `crates/parser/**`
+    #[serde(
+        default,
+        deserialize_with = "globs",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub paths: Vec<String>,
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.description {
+            Some(description) => write!(f, "{}: {}", self.name, description),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl Scope {
+    /// Whether the given name is the name or one of the aliases of the
+    /// scope, ignoring case.
+    pub fn matches(&self, name: &str) -> bool {
+        self.name.to_lowercase() == name.to_lowercase()
+            || self
+                .aliases
+                .iter()
+                .any(|alias| alias.to_lowercase() == name.to_lowercase())
+    }
+}
+
+/// The scopes that are allowed in a commit. An empty registry allows any
+/// scope.
+#[derive(Debug, Clone, Default)]
+pub struct ScopeRegistry {
+    scopes: Vec<Scope>,
+    /// Whether scopes that are not in the registry are allowed
+    allow_custom: bool,
+    /// The path globs of all the scopes, matched in a single pass
+    globs: GlobSet,
+    /// The index of the scope of each glob in `globs`
+    owners: Vec<usize>,
+}
+
+impl PartialEq for ScopeRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        // the globs are built from the scopes
+        self.scopes == other.scopes && self.allow_custom == other.allow_custom
+    }
+}
+
+impl Eq for ScopeRegistry {}
+
+impl ScopeRegistry {
+    /// Creates a registry of the given scopes. The invalid path globs are
+    /// left out, as they are reported when the config is validated.
+    pub fn new(scopes: Vec<Scope>, allow_custom: bool) -> Self {
+        let mut builder = GlobSetBuilder::new();
+        let mut owners = Vec::new();
+        for (i, scope) in scopes.iter().enumerate() {
+            for glob in scope.paths.iter().filter_map(|p| Glob::new(p).ok()) {
+                builder.add(glob);
+                owners.push(i);
+            }
+        }
+        Self {
+            scopes,
+            allow_custom,
+            globs: builder.build().unwrap_or_default(),
+            owners,
+        }
+    }
+
+    /// The registered scopes.
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
+
+    /// Whether scopes that are not in the registry are allowed.
+    pub fn allow_custom(&self) -> bool {
+        self.allow_custom
+    }
+
+    /// Whether any scope is allowed.
+    pub fn is_open(&self) -> bool {
+        self.scopes.is_empty() || self.allow_custom
+    }
+
+    /// Resolves the given scope to the name of the registered scope it
+    /// matches. Unknown scopes are kept as they are when custom scopes are
+    /// allowed, and rejected otherwise.
+    pub fn resolve(&self, scope: &str) -> Result<String, CommitError> {
+        if let Some(registered) = self.scopes.iter().find(|s| s.matches(scope))
+        {
+            return Ok(registered.name.clone());
+        }
+        if self.is_open() {
+            return Ok(scope.to_string());
+        }
+        Err(CommitError::UnknownScopeError {
+            scope: scope.to_string(),
+            suggestion: self.closest(scope),
+        })
+    }
+
+    /// The scope owning the given path, relative to the root of the
+    /// repository, if any. When several scopes own the path, the first one
+    /// wins.
+    pub fn detect(&self, path: &str) -> Option<&Scope> {
+        self.globs
+            .matches(path)
+            .into_iter()
+            .map(|i| self.owners[i])
+            .min()
+            .map(|i| &self.scopes[i])
+    }
+
+    /// The registered scope whose name or alias is the closest to the given
+    /// scope.
+    pub fn closest(&self, scope: &str) -> Option<String> {
+        let scope = scope.to_lowercase();
+        self.scopes
+            .iter()
+            .flat_map(|s| {
+                std::iter::once(&s.name)
+                    .chain(s.aliases.iter())
+                    .map(move |name| (s, name))
+            })
+            .min_by_key(|(_, name)| {
+                strsim::levenshtein(&scope, &name.to_lowercase())
+            })
+            .map(|(s, _)| s.name.clone())
+    }
+}
+
+/// Reads the paths of a scope, each of which must be a valid glob.
+fn globs<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    let paths = Vec::<String>::deserialize(deserializer)?;
+    for path in &paths {
+        Glob::new(path).map_err(serde::de::Error::custom)?;
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commit::error::CommitError;
+
+    use super::{Scope, ScopeRegistry};
+
+    fn registry(allow_custom: bool) -> ScopeRegistry {
+        ScopeRegistry::new(
+            vec![
+                Scope {
+                    name: "parser".to_string(),
+                    aliases: vec!["lexer".to_string()],
+                    paths: vec!["crates/parser/**".to_string()],
+                    ..Scope::default()
+                },
+                Scope {
+                    name: "cli".to_string(),
+                    paths: vec![
+                        "crates/*/src/main.rs".to_string(),
+                        "src/cli/**".to_string(),
+                        "[invalid".to_string(),
+                    ],
+                    ..Scope::default()
+                },
+            ],
+            allow_custom,
+        )
+    }
+
+    #[test]
+    fn resolves_names_and_aliases() {
+        let registry = registry(false);
+        assert_eq!(registry.resolve("parser"), Ok("parser".to_string()));
+        assert_eq!(registry.resolve("Lexer"), Ok("parser".to_string()));
+        assert_eq!(registry.resolve("CLI"), Ok("cli".to_string()));
+    }
+
+    #[test]
+    fn suggests_the_closest_scope() {
+        let registry = registry(false);
+        assert_eq!(
+            registry.resolve("parsre"),
+            Err(CommitError::UnknownScopeError {
+                scope: "parsre".to_string(),
+                suggestion: Some("parser".to_string()),
+            })
+        );
+        assert_eq!(registry.closest("lexr"), Some("parser".to_string()));
+        assert_eq!(registry.closest("cl"), Some("cli".to_string()));
+        assert_eq!(ScopeRegistry::default().closest("cli"), None);
+    }
+
+    #[test]
+    fn keeps_custom_scopes_when_open() {
+        assert_eq!(registry(true).resolve("docs"), Ok("docs".to_string()));
+        assert_eq!(
+            ScopeRegistry::default().resolve("docs"),
+            Ok("docs".to_string())
+        );
+        assert!(registry(false).resolve("docs").is_err());
+    }
+
+    #[test]
+    fn detects_the_scope_of_a_path() {
+        let registry = registry(false);
+        let detect = |path| registry.detect(path).map(|s| s.name.as_str());
+        assert_eq!(detect("crates/parser/src/lib.rs"), Some("parser"));
+        assert_eq!(detect("src/cli/args.rs"), Some("cli"));
+        assert_eq!(detect("crates/cli/src/main.rs"), Some("cli"));
+        // the first scope wins when several own the path
+        assert_eq!(detect("crates/parser/src/main.rs"), Some("parser"));
+        assert_eq!(detect("README.md"), None);
+        assert_eq!(ScopeRegistry::default().detect("src/cli/args.rs"), None);
+    }
+}
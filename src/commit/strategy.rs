@@ -1,11 +1,13 @@
 use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
 
 /// The strategy for the commit type
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize,
 )]
+#[serde(rename_all = "kebab-case")]
 pub enum CaseStrategy {
     /// The scope is all lowercase
     #[default]
@@ -16,6 +18,18 @@ pub enum CaseStrategy {
     Capitalized,
     /// The scope is unchanged
     Unchanged,
+    /// The words are lowercase and joined by hyphens, e.g. `api-server`
+    KebabCase,
+    /// The words are lowercase and joined by underscores, e.g. `api_server`
+    SnakeCase,
+    /// The words are joined, each but the first one capitalized, e.g.
+    /// `apiServer`
+    CamelCase,
+    /// The words are joined, each one capitalized, e.g. `ApiServer`
+    PascalCase,
+    /// The first letter is uppercase, the rest is unchanged, e.g.
+    /// `Add the API server`
+    SentenceCase,
 }
 
 impl Display for CaseStrategy {
@@ -25,25 +39,80 @@ impl Display for CaseStrategy {
             CaseStrategy::Uppercase => write!(f, "uppercase"),
             CaseStrategy::Capitalized => write!(f, "capitalized"),
             CaseStrategy::Unchanged => write!(f, "unchanged"),
+            CaseStrategy::KebabCase => write!(f, "kebab-case"),
+            CaseStrategy::SnakeCase => write!(f, "snake-case"),
+            CaseStrategy::CamelCase => write!(f, "camel-case"),
+            CaseStrategy::PascalCase => write!(f, "pascal-case"),
+            CaseStrategy::SentenceCase => write!(f, "sentence-case"),
         }
     }
 }
 
 impl CaseStrategy {
+    /// Every case strategy, in the order in which they are offered.
+    pub const ALL: [CaseStrategy; 9] = [
+        CaseStrategy::Lowercase,
+        CaseStrategy::Uppercase,
+        CaseStrategy::Capitalized,
+        CaseStrategy::Unchanged,
+        CaseStrategy::KebabCase,
+        CaseStrategy::SnakeCase,
+        CaseStrategy::CamelCase,
+        CaseStrategy::PascalCase,
+        CaseStrategy::SentenceCase,
+    ];
+
     /// Apply the case strategy to the scope
     pub fn apply<T: AsRef<str>>(&self, scope: T) -> String {
         match *self {
             CaseStrategy::Lowercase => scope.as_ref().to_lowercase(),
             CaseStrategy::Uppercase => scope.as_ref().to_uppercase(),
             CaseStrategy::Capitalized => {
-                let mut scope = scope.as_ref().to_lowercase();
-                scope[..1].make_ascii_uppercase();
-                scope
+                capitalize(&scope.as_ref().to_lowercase())
             }
             CaseStrategy::Unchanged => scope.as_ref().to_string(),
+            CaseStrategy::KebabCase => words(scope.as_ref())
+                .iter()
+                .map(|word| word.to_lowercase())
+                .collect::<Vec<String>>()
+                .join("-"),
+            CaseStrategy::SnakeCase => words(scope.as_ref())
+                .iter()
+                .map(|word| word.to_lowercase())
+                .collect::<Vec<String>>()
+                .join("_"),
+            CaseStrategy::CamelCase => words(scope.as_ref())
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    if i == 0 {
+                        word.to_lowercase()
+                    } else {
+                        capitalize(&word.to_lowercase())
+                    }
+                })
+                .collect(),
+            CaseStrategy::PascalCase => words(scope.as_ref())
+                .iter()
+                .map(|word| capitalize(&word.to_lowercase()))
+                .collect(),
+            CaseStrategy::SentenceCase => capitalize(scope.as_ref()),
         }
     }
 
+    /// Whether the strategy joins the words, dropping the white space
+    /// between them. Such strategies only suit identifiers like the type and
+    /// the scope, and are not applied to the subject.
+    pub fn joins_words(&self) -> bool {
+        matches!(
+            self,
+            CaseStrategy::KebabCase
+                | CaseStrategy::SnakeCase
+                | CaseStrategy::CamelCase
+                | CaseStrategy::PascalCase
+        )
+    }
+
     /// Verify that the scope matches the case strategy
     pub fn verify<T: AsRef<str>>(&self, scope: T) -> bool {
         match *self {
@@ -54,11 +123,196 @@ impl CaseStrategy {
             CaseStrategy::Uppercase => {
                 scope.as_ref().to_uppercase() == scope.as_ref()
             }
-            CaseStrategy::Capitalized => {
-                let mut scope = scope.as_ref().to_lowercase();
-                scope[..1].make_ascii_uppercase();
-                scope == scope.as_ref()
+            CaseStrategy::Capitalized
+            | CaseStrategy::KebabCase
+            | CaseStrategy::SnakeCase
+            | CaseStrategy::CamelCase
+            | CaseStrategy::PascalCase
+            | CaseStrategy::SentenceCase => {
+                self.apply(&scope) == scope.as_ref()
             }
         }
     }
 }
+
+/// The case strategy for each of the cased components of a commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaseStrategies {
+    pub commit_type: CaseStrategy,
+    pub scope: CaseStrategy,
+    pub subject: CaseStrategy,
+}
+
+impl Default for CaseStrategies {
+    fn default() -> Self {
+        Self {
+            commit_type: CaseStrategy::Unchanged,
+            scope: CaseStrategy::Lowercase,
+            subject: CaseStrategy::Lowercase,
+        }
+    }
+}
+
+/// Splits the content into words, on white space, hyphens, underscores and
+/// case boundaries such as in `apiServer`, `HTTPServer` or `中文Api`. The
+/// content is walked grapheme by grapheme, so that combining marks stay
+/// attached to their base character.
+fn words(content: &str) -> Vec<String> {
+    let graphemes = content.graphemes(true).collect::<Vec<&str>>();
+    let base = |grapheme: &str| grapheme.chars().next().unwrap_or(' ');
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (i, grapheme) in graphemes.iter().enumerate() {
+        let c = base(grapheme);
+        if c.is_whitespace() || c == '-' || c == '_' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !current.is_empty() {
+            let prev = base(graphemes[i - 1]);
+            let next_is_lower = graphemes
+                .get(i + 1)
+                .is_some_and(|next| base(next).is_lowercase());
+            if !prev.is_uppercase() || next_is_lower {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push_str(grapheme);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Uppercases the first grapheme, leaving the rest unchanged. Graphemes
+/// without case, such as CJK characters or emoji, are left alone.
+fn capitalize(content: &str) -> String {
+    let mut graphemes = content.graphemes(true);
+    match graphemes.next() {
+        Some(first) => first.to_uppercase() + graphemes.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CaseStrategy;
+
+    #[test]
+    fn empty_input_does_not_panic() {
+        for strategy in CaseStrategy::ALL {
+            assert_eq!(strategy.apply(""), "");
+            assert!(strategy.verify(""));
+        }
+    }
+
+    #[test]
+    fn applied_content_is_verified() {
+        let inputs = [
+            "api server",
+            "HTTPServer config",
+            "中文 API",
+            "über alles",
+            "e\u{301}cole",
+            "🎉 party time",
+            "日本語テスト",
+            "Ωmega σοφία",
+        ];
+        for strategy in CaseStrategy::ALL {
+            for input in inputs {
+                let applied = strategy.apply(input);
+                assert!(
+                    strategy.verify(&applied),
+                    "{} did not verify {:?}",
+                    strategy,
+                    applied
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn capitalizes_multi_byte_first_grapheme() {
+        assert_eq!(CaseStrategy::Capitalized.apply("éCOLE"), "École");
+        assert_eq!(CaseStrategy::Capitalized.apply("über"), "Über");
+        assert_eq!(
+            CaseStrategy::Capitalized.apply("e\u{301}cole"),
+            "E\u{301}cole"
+        );
+        assert_eq!(CaseStrategy::SentenceCase.apply("über API"), "Über API");
+    }
+
+    #[test]
+    fn leaves_non_cased_scripts_alone() {
+        for strategy in [
+            CaseStrategy::Lowercase,
+            CaseStrategy::Uppercase,
+            CaseStrategy::Capitalized,
+            CaseStrategy::SentenceCase,
+        ] {
+            assert_eq!(strategy.apply("修复登录"), "修复登录");
+            assert_eq!(strategy.apply("🎉"), "🎉");
+            assert!(strategy.verify("修复登录"));
+        }
+        assert_eq!(CaseStrategy::Capitalized.apply("中文API"), "中文api");
+        assert_eq!(CaseStrategy::SentenceCase.apply("🎉 party"), "🎉 party");
+    }
+
+    #[test]
+    fn splits_mixed_script_words() {
+        assert_eq!(CaseStrategy::KebabCase.apply("中文 API"), "中文-api");
+        assert_eq!(CaseStrategy::SnakeCase.apply("Über Straße"), "über_straße");
+        assert_eq!(CaseStrategy::PascalCase.apply("élan vital"), "ÉlanVital");
+        assert_eq!(CaseStrategy::CamelCase.apply("Ωmega σοφία"), "ωmegaΣοφία");
+    }
+
+    #[test]
+    fn joins_words() {
+        let input = "add HTTPServer config";
+        assert_eq!(
+            CaseStrategy::KebabCase.apply(input),
+            "add-http-server-config"
+        );
+        assert_eq!(
+            CaseStrategy::SnakeCase.apply(input),
+            "add_http_server_config"
+        );
+        assert_eq!(CaseStrategy::CamelCase.apply(input), "addHttpServerConfig");
+        assert_eq!(
+            CaseStrategy::PascalCase.apply(input),
+            "AddHttpServerConfig"
+        );
+        assert_eq!(
+            CaseStrategy::SentenceCase.apply(input),
+            "Add HTTPServer config"
+        );
+        let joining = CaseStrategy::ALL
+            .into_iter()
+            .filter(CaseStrategy::joins_words)
+            .collect::<Vec<CaseStrategy>>();
+        assert_eq!(
+            joining,
+            [
+                CaseStrategy::KebabCase,
+                CaseStrategy::SnakeCase,
+                CaseStrategy::CamelCase,
+                CaseStrategy::PascalCase,
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_case() {
+        assert!(!CaseStrategy::Lowercase.verify("Über"));
+        assert!(!CaseStrategy::Uppercase.verify("über"));
+        assert!(!CaseStrategy::Capitalized.verify("école"));
+        assert!(!CaseStrategy::SentenceCase.verify("über"));
+        assert!(!CaseStrategy::KebabCase.verify("api_server"));
+        assert!(!CaseStrategy::SnakeCase.verify("api-server"));
+        assert!(!CaseStrategy::CamelCase.verify("ApiServer"));
+        assert!(!CaseStrategy::PascalCase.verify("apiServer"));
+    }
+}
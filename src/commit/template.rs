@@ -0,0 +1,625 @@
+use std::{fmt::Display, mem};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::{
+    cmt_type::is_shortcode,
+    commit::Commit,
+    constants::DEFAULT_HEADER_TEMPLATE,
+    error::{ParseError, ParseErrorKind},
+    footer::Footer,
+    limits::split_footer,
+    parse::parse_header,
+};
+
+lazy_static! {
+    static ref WORD: Regex = Regex::new(r"[\w.-]+").unwrap();
+    static ref DEFAULT_TEMPLATE: MessageTemplate =
+        MessageTemplate::new(DEFAULT_HEADER_TEMPLATE, Vec::new()).unwrap();
+}
+
+/// The layout of a commit message. The header and the trailers are text
+/// with placeholders such as `{type}`, `{emoji}`, `{scope}`, `{breaking}`,
+/// `{subject}` or `{footer.Refs}`. The text inside the braces around the
+/// name, as in `{(scope)}`, is only rendered along with a value.
+///
+/// The template is checked and read once, when it is created.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(try_from = "TemplateSource", into = "TemplateSource")]
+pub struct MessageTemplate {
+    source: TemplateSource,
+    header: Vec<Segment>,
+    trailers: Vec<Vec<Segment>>,
+    /// Reads the header
+    pattern: Regex,
+}
+
+/// The template as it is written in the config.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct TemplateSource {
+    /// The first line of the message
+    #[serde(deserialize_with = "header")]
+    header: String,
+    /// The trailers added after the footers of the commit, such as
+    /// `Change-Type: {type}`
+    #[serde(deserialize_with = "trailers")]
+    trailers: Vec<String>,
+}
+
+impl Default for TemplateSource {
+    fn default() -> Self {
+        Self {
+            header: DEFAULT_HEADER_TEMPLATE.to_string(),
+            trailers: Vec::new(),
+        }
+    }
+}
+
+impl TryFrom<TemplateSource> for MessageTemplate {
+    type Error = String;
+
+    fn try_from(source: TemplateSource) -> Result<Self, Self::Error> {
+        check_header(&source.header)?;
+        for trailer in &source.trailers {
+            check_trailer(trailer)?;
+        }
+        let header = segments(&source.header)?;
+        let trailers = source
+            .trailers
+            .iter()
+            .map(|trailer| segments(trailer))
+            .collect::<Result<Vec<Vec<Segment>>, String>>()?;
+        let pattern = Regex::new(&format!("^{}$", pattern(&header)))
+            .map_err(|error| error.to_string())?;
+        Ok(Self {
+            source,
+            header,
+            trailers,
+            pattern,
+        })
+    }
+}
+
+impl From<MessageTemplate> for TemplateSource {
+    fn from(template: MessageTemplate) -> Self {
+        template.source
+    }
+}
+
+impl PartialEq for MessageTemplate {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for MessageTemplate {}
+
+impl Default for MessageTemplate {
+    fn default() -> Self {
+        DEFAULT_TEMPLATE.clone()
+    }
+}
+
+impl MessageTemplate {
+    /// Creates a template from its header and its trailers, once the
+    /// messages rendered from it can be read back.
+    pub fn new(
+        header: impl Into<String>,
+        trailers: Vec<String>,
+    ) -> Result<Self, String> {
+        Self::try_from(TemplateSource {
+            header: header.into(),
+            trailers,
+        })
+    }
+
+    /// Renders the header of a commit.
+    pub fn header(&self, commit: &Commit) -> String {
+        render(&self.header, commit)
+    }
+
+    /// Renders the whole message: the header, the description, the footers
+    /// that the header does not show, and the trailers of the template.
+    pub fn render(&self, commit: &Commit) -> String {
+        let mut content = self.header(commit);
+        if let Some(description) = &commit.description {
+            if !description.is_empty() {
+                content.push_str("\n\n");
+                content.push_str(description);
+            }
+        }
+        let mut footers = commit.footers.clone();
+        for token in self.header_tokens() {
+            if let Some(i) = footers.iter().position(|f| f.token == token) {
+                footers.remove(i);
+            }
+        }
+        let mut lines = footers
+            .iter()
+            .map(|footer| footer.to_string())
+            .collect::<Vec<String>>();
+        lines.extend(
+            self.added_trailers(commit)
+                .iter()
+                .map(|trailer| trailer.to_string()),
+        );
+        if !lines.is_empty() {
+            // the footers join the trailers at the end of the description,
+            // if any, as git only reads the trailers of the last paragraph
+            let has_trailers =
+                commit.description.as_ref().is_some_and(|description| {
+                    !split_footer(description).1.is_empty()
+                });
+            content = content.trim_end().to_string();
+            content.push_str(if has_trailers { "\n" } else { "\n\n" });
+            content.push_str(&lines.join("\n"));
+        }
+        content
+    }
+
+    /// Reads a header rendered from the template. The footers that the
+    /// header shows are added to the commit.
+    pub fn parse_header(&self, header: &str) -> Result<Commit, ParseError> {
+        // the default layout reports what is wrong in the header
+        if self.source.header == DEFAULT_HEADER_TEMPLATE {
+            return parse_header(header);
+        }
+        let captures = self
+            .pattern
+            .captures(header)
+            .ok_or_else(|| self.mismatch(header))?;
+        let fields = self.header.iter().filter_map(|segment| match segment {
+            Segment::Field { field, .. } => Some(field),
+            Segment::Text(_) => None,
+        });
+        let mut commit = Commit::default();
+        for (i, field) in fields.enumerate() {
+            let Some(value) = captures.get(i + 1) else {
+                continue;
+            };
+            let value = value.as_str().to_string();
+            match field {
+                Field::Type => commit.commit_type.name = value,
+                // either form of the gitmoji is read in place of the other
+                Field::Emoji | Field::Shortcode if is_shortcode(&value) => {
+                    commit.commit_type.shortcode = Some(value)
+                }
+                Field::Emoji | Field::Shortcode => {
+                    commit.commit_type.emoji = Some(value)
+                }
+                Field::Scope => commit.scope = Some(value),
+                Field::Breaking => commit.is_breaking_change = true,
+                Field::Subject => commit.subject = value,
+                Field::Footer(token) => {
+                    commit.footers.push(Footer::new(token, value))
+                }
+            }
+        }
+        Ok(commit)
+    }
+
+    /// Where a header that does not follow the template goes wrong: right
+    /// after the longest run of the parts of the template that it starts
+    /// with.
+    fn mismatch(&self, header: &str) -> ParseError {
+        let (end, expected) = (0..=self.header.len())
+            .rev()
+            .find_map(|len| {
+                let prefix = &self.header[..len];
+                let pattern = Regex::new(&format!("^{}", pattern(prefix)));
+                let end = pattern.ok()?.find(header)?.end();
+                Some((end, self.header.get(len)))
+            })
+            .unwrap_or((0, self.header.first()));
+        let expected = match expected {
+            Some(Segment::Text(text)) => format!("`{}`", text),
+            Some(Segment::Field {
+                field,
+                before,
+                after,
+            }) => format!("`{{{}{}{}}}`", before, field, after),
+            None => "the end of the header".to_string(),
+        };
+        ParseError {
+            line: 1,
+            column: header[..end].chars().count() + 1,
+            kind: ParseErrorKind::TemplateMismatchError {
+                expected,
+                template: self.source.header.clone(),
+            },
+        }
+    }
+
+    /// The trailers that the template adds to a commit, leaving out the
+    /// ones whose values are empty.
+    pub fn added_trailers(&self, commit: &Commit) -> Vec<Footer> {
+        self.trailers
+            .iter()
+            .filter_map(|trailer| Footer::parse(&render(trailer, commit)))
+            .filter(|footer| !footer.value.trim().is_empty())
+            .collect()
+    }
+
+    /// The tokens of the footers that the header shows.
+    fn header_tokens(&self) -> Vec<String> {
+        self.header
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Field {
+                    field: Field::Footer(token),
+                    ..
+                } => Some(token.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Checks that the headers rendered from a template can be read back.
+fn check_header(header: &str) -> Result<(), String> {
+    if header.contains('\n') {
+        return Err("the header must be a single line".to_string());
+    }
+    let fields = segments(header)?
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Field { field, .. } => Some(field),
+            Segment::Text(_) => None,
+        })
+        .collect::<Vec<Field>>();
+    for (i, field) in fields.iter().enumerate() {
+        if fields[..i].contains(field) {
+            return Err(format!("the header has `{{{}}}` twice", field));
+        }
+    }
+    for field in [Field::Type, Field::Subject] {
+        if !fields.contains(&field) {
+            return Err(format!("the header has no `{{{}}}`", field));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the lines rendered from a trailer template are read as
+/// trailers.
+fn check_trailer(trailer: &str) -> Result<(), String> {
+    segments(trailer)?;
+    if Footer::parse(trailer).is_none() {
+        return Err(format!(
+            "the trailer `{}` does not start with a token, as in \
+             `Token: value`",
+            trailer
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the header of a template, checked as per `check_header`.
+fn header<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    let header = String::deserialize(deserializer)?;
+    check_header(&header).map_err(serde::de::Error::custom)?;
+    Ok(header)
+}
+
+/// Reads the trailers of a template, each checked as per `check_trailer`.
+fn trailers<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    let trailers = Vec::<String>::deserialize(deserializer)?;
+    for trailer in &trailers {
+        check_trailer(trailer).map_err(serde::de::Error::custom)?;
+    }
+    Ok(trailers)
+}
+
+/// The pattern that reads the parts of a template, with a group for each
+/// placeholder. Only the type and the subject are required.
+fn pattern(segments: &[Segment]) -> String {
+    let mut pattern = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => pattern.push_str(&regex::escape(text)),
+            Segment::Field {
+                field,
+                before,
+                after,
+            } => {
+                let group = format!(
+                    "{}({}){}",
+                    regex::escape(before),
+                    field.pattern(),
+                    regex::escape(after)
+                );
+                if matches!(field, Field::Type | Field::Subject) {
+                    pattern.push_str(&group);
+                } else {
+                    pattern.push_str(&format!("(?:{})?", group));
+                }
+            }
+        }
+    }
+    pattern
+}
+
+/// A value of the commit that a placeholder refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Field {
+    Type,
+    /// The gitmoji of the type, or its shortcode when it has none
+    Emoji,
+    /// The gitmoji shortcode of the type, or its gitmoji when it has none
+    Shortcode,
+    Scope,
+    /// The `!` of a breaking change
+    Breaking,
+    Subject,
+    /// The value of the first footer with the token
+    Footer(String),
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "type" => Some(Field::Type),
+            "emoji" => Some(Field::Emoji),
+            "shortcode" => Some(Field::Shortcode),
+            "scope" => Some(Field::Scope),
+            "breaking" => Some(Field::Breaking),
+            "subject" => Some(Field::Subject),
+            _ => name
+                .strip_prefix("footer.")
+                .filter(|token| Footer::is_valid_token(token))
+                .map(|token| Field::Footer(token.to_string())),
+        }
+    }
+
+    fn value(&self, commit: &Commit) -> String {
+        match self {
+            Field::Type => commit.commit_type.name.clone(),
+            Field::Emoji => {
+                let commit_type = &commit.commit_type;
+                let emoji = commit_type.emoji.as_ref();
+                emoji
+                    .or(commit_type.shortcode.as_ref())
+                    .cloned()
+                    .unwrap_or_default()
+            }
+            Field::Shortcode => {
+                let commit_type = &commit.commit_type;
+                let shortcode = commit_type.shortcode.as_ref();
+                shortcode
+                    .or(commit_type.emoji.as_ref())
+                    .cloned()
+                    .unwrap_or_default()
+            }
+            Field::Scope => commit.scope.clone().unwrap_or_default(),
+            Field::Breaking => {
+                if commit.is_breaking_change { "!" } else { "" }.to_string()
+            }
+            Field::Subject => commit.subject.clone(),
+            Field::Footer(token) => commit
+                .footers
+                .iter()
+                .find(|footer| footer.token == *token)
+                .map(|footer| footer.value.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The pattern of the value when the header is read.
+    fn pattern(&self) -> &'static str {
+        match self {
+            Field::Type => r"[\w-]+",
+            Field::Emoji | Field::Shortcode => {
+                r":[\w+-]+:|\p{Extended_Pictographic}[\p{Extended_Pictographic}\p{Emoji_Modifier}\x{FE0F}\x{200D}]*"
+            }
+            Field::Breaking => "!",
+            _ => ".+?",
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Field::Type => write!(f, "type"),
+            Field::Emoji => write!(f, "emoji"),
+            Field::Shortcode => write!(f, "shortcode"),
+            Field::Scope => write!(f, "scope"),
+            Field::Breaking => write!(f, "breaking"),
+            Field::Subject => write!(f, "subject"),
+            Field::Footer(token) => write!(f, "footer.{}", token),
+        }
+    }
+}
+
+/// A part of a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    /// A placeholder, with the text inside its braces around the name
+    Field {
+        field: Field,
+        before: String,
+        after: String,
+    },
+}
+
+/// Splits a template into text and placeholders. `{{` and `}}` stand for
+/// literal braces.
+fn segments(template: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' if chars.peek() == Some(&c) => {
+                chars.next();
+                text.push(c);
+            }
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => inner.push(c),
+                        None => {
+                            return Err(format!(
+                                "unclosed `{{` in `{}`",
+                                template
+                            ))
+                        }
+                    }
+                }
+                if !text.is_empty() {
+                    segments.push(Segment::Text(mem::take(&mut text)));
+                }
+                segments.push(placeholder(&inner)?);
+            }
+            '}' => return Err(format!("unmatched `}}` in `{}`", template)),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    Ok(segments)
+}
+
+/// Reads the inside of a placeholder, where the first word that names a
+/// field is the name.
+fn placeholder(inner: &str) -> Result<Segment, String> {
+    WORD.find_iter(inner)
+        .find_map(|name| {
+            Field::parse(name.as_str()).map(|field| Segment::Field {
+                field,
+                before: inner[..name.start()].to_string(),
+                after: inner[name.end()..].to_string(),
+            })
+        })
+        .ok_or_else(|| format!("unknown placeholder `{{{}}}`", inner))
+}
+
+/// Renders the parts of a template, leaving out the placeholders whose
+/// values are empty.
+fn render(segments: &[Segment], commit: &Commit) -> String {
+    let mut content = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => content.push_str(text),
+            Segment::Field {
+                field,
+                before,
+                after,
+            } => {
+                let value = field.value(commit);
+                if !value.is_empty() {
+                    content.push_str(before);
+                    content.push_str(&value);
+                    content.push_str(after);
+                }
+            }
+        }
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commit::{
+        cmt_type::CommitType,
+        commit::Commit,
+        error::{ParseError, ParseErrorKind},
+    };
+
+    use super::{check_header, MessageTemplate};
+
+    #[test]
+    fn renders_and_reads_templates() {
+        let template = MessageTemplate::new(
+            "{[footer.Ticket] }{type}{(scope)}{breaking}: {subject}",
+            vec!["Change-Type: {type}".to_string()],
+        )
+        .unwrap();
+        let message = "[GCMT-12] fix(api)!: reject empty names\n\n\
+                       Names were trimmed.\n\n\
+                       Refs #4\n\
+                       Change-Type: fix";
+        let commit = Commit::parse_with(message, &template).unwrap();
+        assert_eq!(commit.subject, "reject empty names");
+        assert_eq!(commit.footers.len(), 2);
+        assert_eq!(commit.footers[0].value, "GCMT-12");
+        assert_eq!(template.render(&commit), message);
+        let commit = Commit::parse_with("fix: x", &template).unwrap();
+        assert_eq!(template.render(&commit), "fix: x\n\nChange-Type: fix");
+        // a trailer that only shares its token with the template is kept
+        let message = "fix: x\n\nChange-Type: hotfix\nChange-Type: fix";
+        let commit = Commit::parse_with(message, &template).unwrap();
+        assert_eq!(commit.footers.len(), 1);
+        assert_eq!(commit.footers[0].value, "hotfix");
+        assert_eq!(template.render(&commit), message);
+        assert!(check_header("{type}: {subject}").is_ok());
+        assert!(check_header("{type}: {subject} {{literal}}").is_ok());
+        assert!(check_header("{type}: {subject").is_err());
+        assert!(check_header("{type}: {title}").is_err());
+        assert!(check_header("{type}{type}: {subject}").is_err());
+        assert!(check_header("{(scope)}: {subject}").is_err());
+        let trailers = vec!["{type}".to_string()];
+        assert!(MessageTemplate::new("{type}: {subject}", trailers).is_err());
+    }
+
+    #[test]
+    fn reports_where_the_header_goes_wrong() {
+        let template = MessageTemplate::new(
+            "{[footer.Ticket] }{type}{(scope)}{breaking}: {subject}",
+            Vec::new(),
+        )
+        .unwrap();
+        let error = |header: &str| {
+            let ParseError { column, kind, .. } =
+                template.parse_header(header).unwrap_err();
+            let ParseErrorKind::TemplateMismatchError { expected, .. } = kind
+            else {
+                panic!("unexpected error {:?}", kind);
+            };
+            (column, expected)
+        };
+        assert_eq!(error("[GCMT-12] fix x"), (14, "`: `".to_string()));
+        assert_eq!(error("[GCMT-12] : x"), (11, "`{type}`".to_string()));
+        assert_eq!(error("修复: "), (5, "`{subject}`".to_string()));
+    }
+
+    #[test]
+    fn reads_gitmoji() {
+        let template =
+            |header: &str| MessageTemplate::new(header, Vec::new()).unwrap();
+        let after = template("{type}{(scope)}{breaking}: {emoji }{subject}");
+        let before = template("{emoji }{type}{(scope)}{breaking}: {subject}");
+        let commit = Commit::parse_with("feat: ✨ add x", &after).unwrap();
+        assert_eq!(commit.commit_type.emoji.as_deref(), Some("✨"));
+        assert_eq!(commit.subject, "add x");
+        assert_eq!(before.header(&commit), "✨ feat: add x");
+        let commit =
+            Commit::parse_with(":recycle: refactor(api): split", &before)
+                .unwrap();
+        assert_eq!(commit.commit_type.shortcode.as_deref(), Some(":recycle:"));
+        assert_eq!(commit.scope.as_deref(), Some("api"));
+        assert_eq!(before.render(&commit), ":recycle: refactor(api): split");
+        let commit = Commit::parse_with("fix: add x", &after).unwrap();
+        assert_eq!(commit.subject, "add x");
+        let mut commit =
+            Commit::parse_with("♻️ refactor: split", &before).unwrap();
+        assert_eq!(commit.commit_type.emoji.as_deref(), Some("♻️"));
+        commit.commit_type = CommitType::new("refactor", None::<&str>)
+            .with_gitmoji("♻️", ":recycle:");
+        assert_eq!(
+            template("{type}: {shortcode }{subject}").render(&commit),
+            "refactor: :recycle: split"
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::mem;
+
+use super::limits::{split_footer, LengthUnit};
+
+/// Reflows the paragraphs of a Markdown text to lines of at most `width`,
+/// as measured in `unit`. Fenced and indented code, headings, tables and the
+/// trailers at the end are kept as they are, list items and quotes are
+/// wrapped under their marker, and words such as URLs are never split, even
+/// when they are longer than `width`.
+pub fn wrap(text: &str, width: usize, unit: LengthUnit) -> String {
+    let (body, footer) = split_footer(text);
+    let mut lines = Vec::<String>::new();
+    let mut paragraph: Option<Paragraph> = None;
+    let mut fence: Option<&str> = None;
+    for line in body {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        if let Some(marker) = fence {
+            lines.push(line.to_string());
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        let is_code = paragraph.is_none()
+            && (indent.starts_with('\t') || indent.len() >= 4);
+        // rules and the underlines of headings are made of a single
+        // character
+        let is_rule = trimmed.len() >= 3
+            && trimmed.chars().next().is_some_and(|first| {
+                "-=*_".contains(first)
+                    && trimmed.trim_end().chars().all(|c| c == first)
+            });
+        let is_verbatim = is_code
+            || is_rule
+            || trimmed.is_empty()
+            || trimmed.starts_with('#')
+            || trimmed.starts_with('|');
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+        }
+        if is_verbatim || fence.is_some() {
+            flush(paragraph.take(), &mut lines, width, unit);
+            lines.push(line.to_string());
+            continue;
+        }
+        let content = match marker_len(trimmed) {
+            Some(len) => {
+                flush(paragraph.take(), &mut lines, width, unit);
+                let first = format!("{}{}", indent, &trimmed[..len]);
+                let rest = if trimmed.starts_with('>') {
+                    first.clone()
+                } else {
+                    " ".repeat(first.chars().count())
+                };
+                paragraph = Some(Paragraph::new(first, rest));
+                &trimmed[len..]
+            }
+            None => trimmed,
+        };
+        let paragraph = paragraph.get_or_insert_with(|| {
+            Paragraph::new(indent.to_string(), indent.to_string())
+        });
+        paragraph
+            .words
+            .extend(content.split_whitespace().map(String::from));
+        // a line ending with two spaces or a backslash is a hard line break
+        if line.ends_with("  ") || line.ends_with('\\') {
+            let rest = paragraph.rest.clone();
+            let done =
+                mem::replace(paragraph, Paragraph::new(rest.clone(), rest));
+            flush(Some(done), &mut lines, width, unit);
+            if line.ends_with("  ") {
+                if let Some(last) = lines.last_mut() {
+                    last.push_str("  ");
+                }
+            }
+        }
+    }
+    flush(paragraph, &mut lines, width, unit);
+    if !footer.is_empty() {
+        if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.extend(footer.iter().map(|line| line.to_string()));
+    }
+    lines.join("\n")
+}
+
+/// The words of a paragraph, along with the prefix of its first line and
+/// the prefix of the following lines.
+#[derive(Debug)]
+struct Paragraph {
+    first: String,
+    rest: String,
+    words: Vec<String>,
+}
+
+impl Paragraph {
+    fn new(first: String, rest: String) -> Self {
+        Self {
+            first,
+            rest,
+            words: Vec::new(),
+        }
+    }
+}
+
+/// Wraps the words of a paragraph into lines.
+fn flush(
+    paragraph: Option<Paragraph>,
+    lines: &mut Vec<String>,
+    width: usize,
+    unit: LengthUnit,
+) {
+    let Some(paragraph) = paragraph else {
+        return;
+    };
+    if paragraph.words.is_empty() {
+        return;
+    }
+    let mut line = paragraph.first.clone();
+    let mut has_words = false;
+    for word in &paragraph.words {
+        let length = unit.measure(&line) + 1 + unit.measure(word);
+        if has_words && length > width {
+            lines.push(line);
+            line = paragraph.rest.clone();
+            has_words = false;
+        }
+        if has_words {
+            line.push(' ');
+        }
+        line.push_str(word);
+        has_words = true;
+    }
+    lines.push(line);
+}
+
+/// The length of the list or quote marker at the start of a line, including
+/// the space after it.
+fn marker_len(line: &str) -> Option<usize> {
+    for marker in ["- ", "* ", "+ ", "> "] {
+        if line.starts_with(marker) {
+            return Some(marker.len());
+        }
+    }
+    let digits = line.find(|c: char| !c.is_ascii_digit())?;
+    let rest = &line[digits..];
+    (digits > 0 && (rest.starts_with(". ") || rest.starts_with(") ")))
+        .then_some(digits + 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commit::limits::LengthUnit;
+
+    use super::wrap;
+
+    #[test]
+    fn wraps_markdown() {
+        let text = "The parser used to accept headers without a subject, \
+                    which made the changelog skip them.\n\
+                    \n\
+                    - first item that is long enough to be wrapped under its \
+                    marker\n\
+                    12. numbered item\n\
+                    \n\
+                    ```\n\
+                    let x = \"a very long line of code that must be kept as it is\";\n\
+                    ```\n\
+                    \n\
+                    \x20   indented code that is long enough to be wrapped otherwise\n\
+                    \n\
+                    See https://example.com/a/very/long/url/that/is/never/split\n\
+                    \n\
+                    Refs: GCMT-12";
+        let expected = "The parser used to accept headers without a\n\
+                        subject, which made the changelog skip them.\n\
+                        \n\
+                        - first item that is long enough to be wrapped\n\
+                        \x20 under its marker\n\
+                        12. numbered item\n\
+                        \n\
+                        ```\n\
+                        let x = \"a very long line of code that must be kept as it is\";\n\
+                        ```\n\
+                        \n\
+                        \x20   indented code that is long enough to be wrapped otherwise\n\
+                        \n\
+                        See\n\
+                        https://example.com/a/very/long/url/that/is/never/split\n\
+                        \n\
+                        Refs: GCMT-12";
+        assert_eq!(wrap(text, 50, LengthUnit::Chars), expected);
+        assert_eq!(wrap(expected, 50, LengthUnit::Chars), expected);
+        assert_eq!(
+            wrap("一二三四五六 七八九十", 12, LengthUnit::Columns),
+            "一二三四五六\n七八九十"
+        );
+    }
+}
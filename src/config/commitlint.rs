@@ -0,0 +1,399 @@
+use std::{fs, path::Path};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::commit::{
+    cmt_type::CommitType, limits::LimitOverrides, scope::Scope,
+    strategy::CaseStrategy,
+};
+
+use super::{config::CaseOverrides, error::ConfigError, preset::Preset};
+
+/// The commitlint config files, in the order in which they are looked for.
+pub const COMMITLINT_FILES: [&str; 10] = [
+    ".commitlintrc",
+    ".commitlintrc.json",
+    ".commitlintrc.yaml",
+    ".commitlintrc.yml",
+    ".commitlintrc.js",
+    ".commitlintrc.cjs",
+    "commitlint.config.js",
+    "commitlint.config.cjs",
+    "commitlint.config.mjs",
+    "commitlint.config.ts",
+];
+
+lazy_static! {
+    static ref SEVERITY: Regex =
+        Regex::new(r"RuleConfigSeverity\.(Disabled|Warning|Error)").unwrap();
+}
+
+/// The part of the config that can be translated from commitlint.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct ImportedConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_custom_scopes: Option<bool>,
+    #[serde(skip_serializing_if = "is_default")]
+    pub case_strategies: CaseOverrides,
+    #[serde(skip_serializing_if = "is_default")]
+    pub limits: LimitOverrides,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<CommitType>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<Scope>,
+}
+
+/// The result of translating a commitlint config.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Import {
+    pub config: ImportedConfig,
+    /// The rules and settings that have no equivalent, with the reason
+    pub untranslated: Vec<String>,
+}
+
+/// Reads a commitlint config file. JSON and YAML files are read as they
+/// are, while for JavaScript and TypeScript files, the exported object
+/// literal is read as JSON5.
+pub fn read_commitlint(path: &Path) -> Result<Value, ConfigError> {
+    let content = fs::read_to_string(path).map_err(|error| {
+        ConfigError::ReadError(path.to_path_buf(), error.to_string())
+    })?;
+    let parse_error =
+        |message: String| ConfigError::ParseError(path.to_path_buf(), message);
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => json5::from_str(&content)
+            .map_err(|error| parse_error(error.to_string())),
+        Some("yaml" | "yml") => serde_yaml::from_str(&content)
+            .map_err(|error| parse_error(error.to_string())),
+        Some("js" | "cjs" | "mjs" | "ts") => {
+            let literal = exported_object(&content).ok_or_else(|| {
+                parse_error("could not find the exported config".to_string())
+            })?;
+            let literal =
+                SEVERITY.replace_all(literal, |caps: &regex::Captures| {
+                    match &caps[1] {
+                        "Disabled" => "0",
+                        "Warning" => "1",
+                        _ => "2",
+                    }
+                });
+            json5::from_str(&literal).map_err(|error| {
+                parse_error(format!(
+                    "{} (only configs written as a plain object literal can \
+                     be imported)",
+                    error
+                ))
+            })
+        }
+        // `.commitlintrc` may hold either JSON or YAML
+        _ => json5::from_str(&content).or_else(|_| {
+            serde_yaml::from_str(&content)
+                .map_err(|error| parse_error(error.to_string()))
+        }),
+    }
+}
+
+/// Translates a commitlint config into gcmt's config.
+pub fn translate(commitlint: &Value) -> Import {
+    let mut import = Import::default();
+    let Some(settings) = commitlint.as_object() else {
+        import
+            .untranslated
+            .push("the config is not an object".to_string());
+        return import;
+    };
+    for (key, value) in settings {
+        match key.as_str() {
+            "rules" => {}
+            "extends" => {
+                let extended = match value {
+                    Value::Array(items) => items.clone(),
+                    value => vec![value.clone()],
+                };
+                for item in extended {
+                    import.untranslated.push(format!(
+                        "extends {}: shared configs are not followed",
+                        item
+                    ));
+                }
+            }
+            key => import.untranslated.push(format!("{}: not supported", key)),
+        }
+    }
+    let Some(rules) = settings.get("rules").and_then(Value::as_object) else {
+        return import;
+    };
+    for (name, rule) in rules {
+        if let Err(reason) = translate_rule(&mut import.config, name, rule) {
+            import.untranslated.push(format!("{}: {}", name, reason));
+        }
+    }
+    import
+}
+
+/// Translates a rule, written as `[level, when, value]`.
+fn translate_rule(
+    config: &mut ImportedConfig,
+    name: &str,
+    rule: &Value,
+) -> Result<(), String> {
+    let parts = rule.as_array().map(Vec::as_slice).unwrap_or_default();
+    let level = match parts.first() {
+        Some(level) => level.as_u64().ok_or("invalid level")?,
+        None => return Err("expected [level, when, value]".to_string()),
+    };
+    // disabled rules have nothing to enforce
+    if level == 0 {
+        return Ok(());
+    }
+    let when = parts.get(1).and_then(Value::as_str).unwrap_or("always");
+    let value = parts.get(2).unwrap_or(&Value::Null);
+    let is_supported = matches!(
+        name,
+        "type-enum"
+            | "scope-enum"
+            | "type-case"
+            | "scope-case"
+            | "subject-case"
+            | "header-max-length"
+            | "body-max-line-length"
+            | "footer-max-line-length"
+    );
+    if !is_supported {
+        return Err("no equivalent".to_string());
+    }
+    if when != "always" {
+        return Err(format!("`{}` is not supported", when));
+    }
+    // gcmt has no warnings, so the rule would become an error
+    if level == 1 {
+        return Err("warnings are not enforced".to_string());
+    }
+    match name {
+        "type-enum" => {
+            let presets = Preset::default().types();
+            config.types = strings(value)?
+                .into_iter()
+                .map(|name| {
+                    presets
+                        .iter()
+                        .find(|preset| preset.name == name)
+                        .cloned()
+                        .unwrap_or_else(|| CommitType::new(name, None::<&str>))
+                })
+                .collect();
+        }
+        "scope-enum" => {
+            config.scopes = strings(value)?
+                .into_iter()
+                .map(|name| Scope {
+                    name,
+                    ..Scope::default()
+                })
+                .collect();
+            // an empty list allows any scope
+            if !config.scopes.is_empty() {
+                config.allow_custom_scopes = Some(false);
+            }
+        }
+        "type-case" => config.case_strategies.commit_type = Some(case(value)?),
+        "scope-case" => config.case_strategies.scope = Some(case(value)?),
+        "subject-case" => {
+            let strategy = case(value)?;
+            if strategy.joins_words() {
+                return Err(format!(
+                    "{} cannot be applied to the subject",
+                    strategy
+                ));
+            }
+            config.case_strategies.subject = Some(strategy);
+        }
+        "header-max-length" => config.limits.header = Some(length(value)?),
+        "body-max-line-length" => {
+            config.limits.body_line = Some(length(value)?)
+        }
+        _ => config.limits.footer_line = Some(length(value)?),
+    }
+    Ok(())
+}
+
+fn strings(value: &Value) -> Result<Vec<String>, String> {
+    value
+        .as_array()
+        .and_then(|items| {
+            items
+                .iter()
+                .map(|item| item.as_str().map(String::from))
+                .collect::<Option<Vec<String>>>()
+        })
+        .ok_or_else(|| "expected a list of names".to_string())
+}
+
+fn case(value: &Value) -> Result<CaseStrategy, String> {
+    let name = match value {
+        Value::String(name) => name.as_str(),
+        Value::Array(items) if items.len() == 1 => {
+            items[0].as_str().ok_or("expected a case")?
+        }
+        Value::Array(_) => {
+            return Err("only a single case can be translated".to_string())
+        }
+        _ => return Err("expected a case".to_string()),
+    };
+    match name {
+        "lower-case" | "lowercase" => Ok(CaseStrategy::Lowercase),
+        "upper-case" | "uppercase" => Ok(CaseStrategy::Uppercase),
+        "kebab-case" => Ok(CaseStrategy::KebabCase),
+        "snake-case" => Ok(CaseStrategy::SnakeCase),
+        "camel-case" => Ok(CaseStrategy::CamelCase),
+        "pascal-case" => Ok(CaseStrategy::PascalCase),
+        "sentence-case" | "sentencecase" => Ok(CaseStrategy::SentenceCase),
+        name => Err(format!("no equivalent of `{}`", name)),
+    }
+}
+
+fn length(value: &Value) -> Result<usize, String> {
+    value
+        .as_u64()
+        .and_then(|length| usize::try_from(length).ok())
+        .ok_or_else(|| "expected a length".to_string())
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// The object literal exported by a JavaScript or TypeScript config, either
+/// directly or through a variable.
+fn exported_object(source: &str) -> Option<&str> {
+    let start = ["module.exports", "export default"]
+        .iter()
+        .find_map(|export| Some(source.find(export)? + export.len()))?;
+    let rest = source[start..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest).trim_start();
+    if rest.starts_with('{') {
+        return balanced(rest);
+    }
+    // `export default config`, where `config` is declared earlier
+    let name = rest
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .next()
+        .filter(|name| !name.is_empty())?;
+    let declaration = [
+        format!("const {}", name),
+        format!("let {}", name),
+        format!("var {}", name),
+    ]
+    .iter()
+    .find_map(|declaration| source.find(declaration.as_str()))?;
+    let rest = &source[declaration..];
+    let rest = &rest[rest.find('=')?..];
+    balanced(&rest[rest.find('{')?..])
+}
+
+/// The text up to the brace that closes the one `text` starts with, skipping
+/// over strings and comments.
+fn balanced(text: &str) -> Option<&str> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if let Some(q) = quote {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                c if c == q => quote = None,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '/' if chars.peek().map(|(_, c)| *c) == Some('/') => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+            }
+            '/' if chars.peek().map(|(_, c)| *c) == Some('*') => {
+                chars.next();
+                let mut prev = ' ';
+                for (_, c) in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commit::strategy::CaseStrategy;
+
+    use super::{exported_object, translate};
+
+    #[test]
+    fn translates_rules() {
+        let commitlint = json5::from_str(
+            r#"{
+                extends: ["@commitlint/config-conventional"],
+                rules: {
+                    "type-enum": [2, "always", ["feat", "hotfix"]],
+                    "scope-enum": [2, "always", ["api", "ui"]],
+                    "subject-case": [2, "always", "sentence-case"],
+                    "scope-case": [2, "never", ["upper-case"]],
+                    "header-max-length": [2, "always", 50],
+                    "body-max-line-length": [1, "always", 100],
+                    "body-leading-blank": [2, "always"],
+                    "footer-max-line-length": [0, "always", 100],
+                },
+            }"#,
+        )
+        .unwrap();
+        let import = translate(&commitlint);
+        let config = import.config;
+        assert_eq!(config.types.len(), 2);
+        assert_eq!(
+            config.types[0].description.as_deref(),
+            Some("A new feature")
+        );
+        assert_eq!(config.types[1].description, None);
+        assert_eq!(config.scopes.len(), 2);
+        assert_eq!(config.allow_custom_scopes, Some(false));
+        assert_eq!(
+            config.case_strategies.subject,
+            Some(CaseStrategy::SentenceCase)
+        );
+        assert_eq!(config.case_strategies.scope, None);
+        assert_eq!(config.limits.header, Some(50));
+        assert_eq!(config.limits.body_line, None);
+        assert_eq!(config.limits.footer_line, None);
+        assert_eq!(import.untranslated.len(), 4);
+        assert!(import.untranslated.contains(
+            &"body-max-line-length: warnings are not enforced".into()
+        ));
+    }
+
+    #[test]
+    fn finds_the_exported_object() {
+        let source = "// a { comment\n\
+                      const config = { rules: { 'a': '}' } };\n\
+                      export default config;\n";
+        assert_eq!(exported_object(source), Some("{ rules: { 'a': '}' } }"));
+        let source = "module.exports = {rules: {}} /* } */";
+        assert_eq!(exported_object(source), Some("{rules: {}}"));
+    }
+}
@@ -0,0 +1,230 @@
+use std::ffi::OsString;
+
+use serde::{Deserialize, Serialize};
+
+use crate::commit::{
+    builder::CommitBuilder,
+    cmt_type::CommitType,
+    commit::Commit,
+    constants::DEFAULT_WRAP_WIDTH,
+    footer::{BreakingChangeStyle, FooterField},
+    limits::Limits,
+    scope::{Scope, ScopeRegistry},
+    strategy::{CaseStrategies, CaseStrategy},
+    template::MessageTemplate,
+};
+
+use super::{
+    flow::{Flow, Step},
+    preset::Preset,
+};
+
+/// Represents the configuration of gcmt, as read from a `.gcmt.toml` file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The commit types that can be selected
+    pub types: Vec<CommitType>,
+    /// The case strategy used for the scope and the subject
+    pub case_strategy: CaseStrategy,
+    /// Per-component overrides of the case strategy
+    pub case_strategies: CaseOverrides,
+    /// The length limits of the commit message
+    pub limits: Limits,
+    /// The scopes that can be selected. When empty, any scope is allowed
+    pub scopes: Vec<Scope>,
+    /// Whether scopes that are not listed in `scopes` are allowed
+    pub allow_custom_scopes: bool,
+    /// The editor used to write the description
+    pub editor: String,
+    /// The footers that can be asked for in the prompt flow
+    pub footers: Vec<FooterField>,
+    /// How breaking changes are marked in the commit message
+    pub breaking_change_style: BreakingChangeStyle,
+    /// The width to wrap the description to, or 0 to leave it as it is.
+    /// The body line limit, when lower, is used instead
+    pub wrap_width: usize,
+    /// The layout of the commit message
+    pub template: MessageTemplate,
+    /// The prompt flow
+    pub flow: Flow,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            types: Preset::default().types(),
+            case_strategy: CaseStrategy::Lowercase,
+            case_strategies: CaseOverrides::default(),
+            limits: Limits::default(),
+            scopes: Vec::new(),
+            allow_custom_scopes: false,
+            editor: "nvim".to_string(),
+            footers: Vec::new(),
+            breaking_change_style: BreakingChangeStyle::default(),
+            wrap_width: DEFAULT_WRAP_WIDTH,
+            template: MessageTemplate::default(),
+            flow: Flow::default(),
+        }
+    }
+}
+
+impl Config {
+    /// The effective case strategy of each component. The scope and the
+    /// subject fall back to `case_strategy`, while the type is left
+    /// unchanged unless it is overridden. A `case_strategy` that joins words
+    /// is not applied to the subject, which is left lowercase instead.
+    pub fn case_strategies(&self) -> CaseStrategies {
+        let overrides = &self.case_strategies;
+        let subject = if self.case_strategy.joins_words() {
+            CaseStrategy::Lowercase
+        } else {
+            self.case_strategy
+        };
+        CaseStrategies {
+            commit_type: overrides
+                .commit_type
+                .unwrap_or(CaseStrategy::Unchanged),
+            scope: overrides.scope.unwrap_or(self.case_strategy),
+            subject: overrides.subject.unwrap_or(subject),
+        }
+    }
+
+    /// Creates a commit builder that enforces this config.
+    pub fn builder(&self) -> CommitBuilder {
+        let mut builder = Commit::builder(self.case_strategies());
+        builder.limits = self.limits.clone();
+        builder.scopes =
+            ScopeRegistry::new(self.scopes.clone(), self.allow_custom_scopes);
+        builder.footer_fields = self.footers.clone();
+        builder.breaking_change_style = self.breaking_change_style;
+        builder.wrap_width = self.wrap_width;
+        builder.template = self.template.clone();
+        builder
+    }
+
+    /// Checks the values that depend on each other, or on the values of
+    /// other items. Returns every problem along with the key of the
+    /// offending value.
+    pub fn check(&self) -> Vec<(String, String)> {
+        let mut problems = Vec::new();
+        if let Some(subject) = self.case_strategies.subject {
+            if subject.joins_words() {
+                problems.push((
+                    "case_strategies.subject".to_string(),
+                    format!(
+                        "`{}` joins the words and cannot be applied to the \
+                         subject",
+                        subject
+                    ),
+                ));
+            }
+        }
+        let scope_case = self.case_strategies().scope;
+        for (i, scope) in self.scopes.iter().enumerate() {
+            if !scope_case.verify(&scope.name) {
+                problems.push((
+                    format!("scopes.{}.name", i),
+                    format!(
+                        "the scope `{}` is not {}, so it could never be \
+                         selected",
+                        scope.name, scope_case
+                    ),
+                ));
+            }
+        }
+        let names = |items: Vec<&str>, key: &str, field: &str| {
+            items
+                .iter()
+                .enumerate()
+                .filter(|(i, name)| items[..*i].contains(name))
+                .map(|(i, name)| {
+                    (
+                        format!("{}.{}.{}", key, i, field),
+                        format!("duplicate {} `{}` in `{}`", field, name, key),
+                    )
+                })
+                .collect::<Vec<(String, String)>>()
+        };
+        let types = self
+            .types
+            .iter()
+            .map(|commit_type| commit_type.name.as_str())
+            .collect::<Vec<&str>>();
+        let tokens = self
+            .footers
+            .iter()
+            .map(|field| field.token.as_str())
+            .collect::<Vec<&str>>();
+        problems.extend(names(types.clone(), "types", "name"));
+        problems.extend(names(
+            self.scopes
+                .iter()
+                .map(|scope| scope.name.as_str())
+                .collect(),
+            "scopes",
+            "name",
+        ));
+        problems.extend(names(tokens.clone(), "footers", "token"));
+        // the footers and the types may come from different files, so the
+        // types can only be known once they are merged
+        for (i, field) in self.footers.iter().enumerate() {
+            for (j, name) in field.required_for.iter().enumerate() {
+                if !types.contains(&name.as_str()) {
+                    problems.push((
+                        format!("footers.{}.required_for.{}", i, j),
+                        format!(
+                            "the `{}` footer is required for the unknown type \
+                             `{}`",
+                            field.token, name
+                        ),
+                    ));
+                }
+            }
+        }
+        problems.extend(self.flow.check(&tokens));
+        problems
+    }
+
+    /// The prompt flow, where the footers that the steps do not list are
+    /// asked for right after the description.
+    pub fn flow(&self) -> Flow {
+        let mut flow = self.flow.clone();
+        let position = flow
+            .steps
+            .iter()
+            .position(|step| *step == Step::Description)
+            .or_else(|| {
+                flow.steps.iter().position(|step| *step == Step::Subject)
+            })
+            .map_or(flow.steps.len(), |i| i + 1);
+        let missing = self
+            .footers
+            .iter()
+            .map(|field| Step::Footer(field.token.clone()))
+            .filter(|step| !flow.steps.contains(step))
+            .collect::<Vec<Step>>();
+        flow.steps.splice(position..position, missing);
+        flow
+    }
+
+    /// The editor to use for writing the description.
+    pub fn editor(&self) -> OsString {
+        OsString::from(&self.editor)
+    }
+}
+
+/// Overrides the case strategy of individual components.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize,
+)]
+#[serde(default, deny_unknown_fields)]
+pub struct CaseOverrides {
+    /// The case strategy of the commit type
+    #[serde(rename = "type")]
+    pub commit_type: Option<CaseStrategy>,
+    /// The case strategy of the scope
+    pub scope: Option<CaseStrategy>,
+    /// The case strategy of the subject
+    pub subject: Option<CaseStrategy>,
+}
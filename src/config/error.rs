@@ -0,0 +1,73 @@
+use std::{error::Error, fmt::Display, path::PathBuf};
+
+use super::{layer::ConfigSource, validate::Diagnostic};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    ReadError(PathBuf, String),
+    ParseError(PathBuf, String),
+    InvalidValueError(ConfigSource, String),
+    MergeError(String),
+    InvalidConfigError(Vec<Diagnostic>),
+    CyclicExtendsError(Vec<PathBuf>),
+    LockedKeyError {
+        key: String,
+        locked_by: ConfigSource,
+        source: ConfigSource,
+    },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            ConfigError::ReadError(path, reason) => {
+                write!(
+                    f,
+                    "Could not read the config file {}: {}",
+                    path.display(),
+                    reason
+                )
+            }
+            ConfigError::ParseError(path, reason) => {
+                write!(
+                    f,
+                    "Could not parse the config file {}: {}",
+                    path.display(),
+                    reason
+                )
+            }
+            ConfigError::InvalidValueError(source, reason) => {
+                write!(f, "Invalid config value from {}: {}", source, reason)
+            }
+            ConfigError::MergeError(reason) => {
+                write!(f, "Could not merge the config layers: {}", reason)
+            }
+            ConfigError::InvalidConfigError(diagnostics) => {
+                let lines = diagnostics
+                    .iter()
+                    .map(|diagnostic| diagnostic.to_string())
+                    .collect::<Vec<String>>();
+                write!(f, "{}", lines.join("\n"))
+            }
+            ConfigError::CyclicExtendsError(chain) => {
+                let paths = chain
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<String>>();
+                write!(f, "Cyclic extends: {}", paths.join(" -> "))
+            }
+            ConfigError::LockedKeyError {
+                key,
+                locked_by,
+                source,
+            } => write!(
+                f,
+                "`{}` is locked by {} and cannot be overridden by {}",
+                key, locked_by, source
+            ),
+        }
+    }
+}
+
+impl Error for ConfigError {}
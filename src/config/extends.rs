@@ -0,0 +1,183 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use toml::{Table, Value};
+
+use super::{error::ConfigError, layer::ConfigSource, validate::validate_file};
+
+/// The key listing the files that a config file extends.
+pub const EXTENDS_KEY: &str = "extends";
+/// The key listing the inherited commit types to drop.
+pub const REMOVE_TYPES_KEY: &str = "remove_types";
+
+/// Reads a config file, merged on top of the files it extends. The extended
+/// files are merged in order, each one on top of the previous ones, and
+/// their paths are relative to the file that extends them.
+pub fn read_file(
+    path: &Path,
+    source: &ConfigSource,
+) -> Result<Table, ConfigError> {
+    read_chain(path, source, &mut Vec::new())
+}
+
+fn read_chain(
+    path: &Path,
+    source: &ConfigSource,
+    chain: &mut Vec<PathBuf>,
+) -> Result<Table, ConfigError> {
+    let canonical = path.canonicalize().map_err(|error| {
+        ConfigError::ReadError(path.to_path_buf(), error.to_string())
+    })?;
+    if let Some(start) = chain.iter().position(|item| *item == canonical) {
+        let mut cycle = chain[start..].to_vec();
+        cycle.push(canonical);
+        return Err(ConfigError::CyclicExtendsError(cycle));
+    }
+    let content = fs::read_to_string(path).map_err(|error| {
+        ConfigError::ReadError(path.to_path_buf(), error.to_string())
+    })?;
+    // validating each file on its own reports the problems against the
+    // file that caused them, rather than against the merged result
+    let diagnostics = validate_file(&content, source);
+    if !diagnostics.is_empty() {
+        return Err(ConfigError::InvalidConfigError(diagnostics));
+    }
+    let mut table = toml::from_str::<Table>(&content).map_err(|error| {
+        ConfigError::ParseError(path.to_path_buf(), error.to_string())
+    })?;
+    let extends = strings(table.remove(EXTENDS_KEY));
+    let remove_types = strings(table.remove(REMOVE_TYPES_KEY));
+    if extends.is_empty() {
+        return Ok(table);
+    }
+    chain.push(canonical);
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut base = Table::new();
+    for extended in extends {
+        let extended = dir.join(extended);
+        let source = source.with_path(extended.clone());
+        merge_extended(&mut base, read_chain(&extended, &source, chain)?);
+    }
+    chain.pop();
+    if let Some(Value::Array(types)) = base.get_mut("types") {
+        types.retain(|item| {
+            name(item).is_none_or(|name| {
+                !remove_types.iter().any(|removed| removed == name)
+            })
+        });
+    }
+    merge_extended(&mut base, table);
+    Ok(base)
+}
+
+/// Merges a config file on top of the files it extends. Tables are merged
+/// key by key and commit types by name, while any other value replaces the
+/// inherited one.
+pub fn merge_extended(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => {
+                merge_extended(base, overlay)
+            }
+            (Some(Value::Array(base)), Value::Array(overlay))
+                if key == "types" =>
+            {
+                merge_types(base, overlay)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_types(base: &mut Vec<Value>, overlay: Vec<Value>) {
+    for item in overlay {
+        let inherited = name(&item).and_then(|name| {
+            base.iter_mut()
+                .find(|other| self::name(other) == Some(name))
+        });
+        match (inherited, item) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => {
+                merge_extended(base, overlay)
+            }
+            (_, item) => base.push(item),
+        }
+    }
+}
+
+/// The name of a commit type.
+fn name(item: &Value) -> Option<&str> {
+    item.get("name")?.as_str()
+}
+
+fn strings(value: Option<Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(items)) => items
+            .into_iter()
+            .filter_map(|item| item.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::config::{error::ConfigError, layer::ConfigSource};
+
+    use super::read_file;
+
+    #[test]
+    fn merges_extended_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("shared")).unwrap();
+        fs::write(
+            dir.join("shared/base.toml"),
+            "case_strategy = \"uppercase\"\n\
+             [limits]\nheader = 50\nbody_line = 80\n\
+             [[types]]\nname = \"feat\"\ndescription = \"A feature\"\n\
+             [[types]]\nname = \"fix\"\n\
+             [[types]]\nname = \"chore\"\n",
+        )
+        .unwrap();
+        let path = dir.join(".gcmt.toml");
+        fs::write(
+            &path,
+            "extends = [\"shared/base.toml\"]\n\
+             remove_types = [\"chore\"]\n\
+             [limits]\nheader = 60\n\
+             [[types]]\nname = \"feat\"\nrequires_scope = true\n\
+             [[types]]\nname = \"docs\"\n",
+        )
+        .unwrap();
+        let table = read_file(&path, &ConfigSource::Repo(path.clone()));
+        let expected = toml::from_str(
+            "case_strategy = \"uppercase\"\n\
+             [limits]\nheader = 60\nbody_line = 80\n\
+             [[types]]\nname = \"feat\"\ndescription = \"A feature\"\n\
+             requires_scope = true\n\
+             [[types]]\nname = \"fix\"\n\
+             [[types]]\nname = \"docs\"\n",
+        );
+        assert_eq!(table, Ok(expected.unwrap()));
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("a.toml"), "extends = [\"b.toml\"]\n").unwrap();
+        fs::write(dir.join("b.toml"), "extends = [\"a.toml\"]\n").unwrap();
+        let path = dir.join("a.toml");
+        let result = read_file(&path, &ConfigSource::Repo(path.clone()));
+        assert!(matches!(
+            result,
+            Err(ConfigError::CyclicExtendsError(cycle)) if cycle.len() == 3
+        ));
+    }
+}
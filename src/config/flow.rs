@@ -0,0 +1,215 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+/// The prompts of `gcmt`, in the order in which they are asked.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Flow {
+    /// The steps of the flow, in order
+    pub steps: Vec<Step>,
+    /// The answers of the steps that are not to be asked
+    pub defaults: FlowDefaults,
+    /// Custom texts of the prompts, by step name
+    pub prompts: BTreeMap<String, String>,
+}
+
+impl Default for Flow {
+    fn default() -> Self {
+        Self {
+            steps: vec![
+                Step::Stage,
+                Step::Type,
+                Step::Scope,
+                Step::Subject,
+                Step::Description,
+                Step::BreakingChange,
+                Step::Review,
+                Step::Push,
+            ],
+            defaults: FlowDefaults::default(),
+            prompts: BTreeMap::new(),
+        }
+    }
+}
+
+impl Flow {
+    /// The custom text of the prompt of a step, or the given default.
+    pub fn prompt(&self, step: &Step, default: impl AsRef<str>) -> String {
+        self.prompts
+            .get(&step.to_string())
+            .cloned()
+            .unwrap_or_else(|| default.as_ref().to_string())
+    }
+
+    /// Checks that the steps make up a flow that can produce a commit,
+    /// given the tokens of the footers defined in the config. Returns every
+    /// problem along with the key of the offending value.
+    pub fn check(&self, footers: &[&str]) -> Vec<(String, String)> {
+        let mut problems = Vec::new();
+        let step_key = |i: usize| format!("flow.steps.{}", i);
+        for (i, step) in self.steps.iter().enumerate() {
+            if self.steps[..i].contains(step) {
+                problems
+                    .push((step_key(i), format!("duplicate step `{}`", step)));
+            }
+            if let Step::Footer(token) = step {
+                if !footers.contains(&token.as_str()) {
+                    problems.push((
+                        step_key(i),
+                        format!(
+                            "unknown step `{}`, expected a built-in step or \
+                             the token of one of the `footers`",
+                            token
+                        ),
+                    ));
+                }
+            }
+        }
+        for step in [Step::Type, Step::Subject] {
+            if !self.steps.contains(&step) {
+                problems.push((
+                    "flow.steps".to_string(),
+                    format!("missing step `{}`", step),
+                ));
+            }
+        }
+        let position = |step: Step| self.steps.iter().position(|s| *s == step);
+        if let Some(stage) = position(Step::Stage).filter(|i| *i > 0) {
+            problems.push((
+                step_key(stage),
+                "`stage` must be the first step".to_string(),
+            ));
+        }
+        if let (Some(review), Some(push)) =
+            (position(Step::Review), position(Step::Push))
+        {
+            if push < review {
+                problems.push((
+                    step_key(push),
+                    "`push` must come after `review`".to_string(),
+                ));
+            }
+        }
+        for key in self.prompts.keys() {
+            if !self.steps.iter().any(|step| step.to_string() == *key) {
+                problems.push((
+                    format!("flow.prompts.{}", key),
+                    format!("`{}` is not a step of the flow", key),
+                ));
+            }
+        }
+        problems
+    }
+}
+
+/// The answers of the steps that are not to be asked.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FlowDefaults {
+    /// Whether commits are breaking changes
+    pub breaking_change: Option<bool>,
+    /// Whether to push after committing
+    pub push: Option<bool>,
+    /// The scope of the commits
+    pub scope: Option<String>,
+}
+
+/// A step of the prompt flow.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum Step {
+    Stage,
+    Type,
+    Scope,
+    Subject,
+    Description,
+    BreakingChange,
+    Review,
+    Push,
+    /// Asks for the footer with the given token
+    Footer(String),
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Step::Stage => write!(f, "stage"),
+            Step::Type => write!(f, "type"),
+            Step::Scope => write!(f, "scope"),
+            Step::Subject => write!(f, "subject"),
+            Step::Description => write!(f, "description"),
+            Step::BreakingChange => write!(f, "breaking_change"),
+            Step::Review => write!(f, "review"),
+            Step::Push => write!(f, "push"),
+            Step::Footer(token) => write!(f, "{}", token),
+        }
+    }
+}
+
+impl From<String> for Step {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "stage" => Step::Stage,
+            "type" => Step::Type,
+            "scope" => Step::Scope,
+            "subject" => Step::Subject,
+            "description" => Step::Description,
+            "breaking_change" => Step::BreakingChange,
+            "review" => Step::Review,
+            "push" => Step::Push,
+            _ => Step::Footer(name),
+        }
+    }
+}
+
+impl From<Step> for String {
+    fn from(step: Step) -> Self {
+        step.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Flow, Step};
+
+    fn check(steps: &[&str]) -> Vec<(String, String)> {
+        let flow = Flow {
+            steps: steps
+                .iter()
+                .map(|step| Step::from(step.to_string()))
+                .collect(),
+            ..Flow::default()
+        };
+        flow.check(&["Refs"])
+    }
+
+    #[test]
+    fn checks_the_steps() {
+        assert!(Flow::default().check(&[]).is_empty());
+        assert!(check(&["type", "subject", "Refs", "review"]).is_empty());
+        assert!(!check(&["type", "subject", "Fixes"]).is_empty());
+        assert!(!check(&["type", "scope", "review"]).is_empty());
+        assert!(!check(&["type", "subject", "type"]).is_empty());
+        assert!(!check(&["type", "stage", "subject"]).is_empty());
+        assert!(!check(&["type", "subject", "push", "review"]).is_empty());
+    }
+
+    #[test]
+    fn reports_every_problem() {
+        let keys = check(&["stage", "Fixes", "stage", "push", "review"])
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            keys,
+            vec![
+                "flow.steps.1",
+                "flow.steps.2",
+                "flow.steps",
+                "flow.steps",
+                "flow.steps.3"
+            ]
+        );
+    }
+}
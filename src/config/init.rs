@@ -0,0 +1,147 @@
+use serde::Serialize;
+
+use crate::commit::{
+    cmt_type::CommitType, scope::Scope, strategy::CaseStrategy,
+};
+
+use super::preset::Preset;
+
+/// The answers given to `gcmt init`, from which the config file is written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitConfig {
+    pub preset: Preset,
+    pub case_strategy: CaseStrategy,
+    /// The names of the top-level directories that become scopes
+    pub scopes: Vec<String>,
+    pub header: usize,
+    pub body_line: Option<usize>,
+}
+
+impl InitConfig {
+    /// The scopes for the selected directories, each one owning the paths
+    /// under its directory.
+    pub fn scopes(&self) -> Vec<Scope> {
+        self.scopes
+            .iter()
+            .map(|dir| Scope {
+                name: self.case_strategy.apply(dir),
+                paths: vec![format!("{}/**", dir)],
+                ..Scope::default()
+            })
+            .collect()
+    }
+
+    /// The content of the config file. Only the values that were asked for
+    /// are written, so that everything else keeps following the defaults.
+    pub fn render(&self) -> Result<String, toml::ser::Error> {
+        // the subject would be left lowercase with a warning otherwise
+        let case_strategies =
+            self.case_strategy
+                .joins_words()
+                .then_some(InitCaseStrategies {
+                    subject: CaseStrategy::Lowercase,
+                });
+        let file = InitFile {
+            case_strategy: self.case_strategy,
+            case_strategies,
+            limits: InitLimits {
+                header: self.header,
+                body_line: self.body_line,
+            },
+            types: self.preset.types(),
+            scopes: self.scopes(),
+            template: self
+                .preset
+                .header_template()
+                .map(|header| InitTemplate { header }),
+        };
+        Ok(format!(
+            "# Created by `gcmt init` from the `{}` preset\n\n{}",
+            self.preset.name(),
+            toml::to_string_pretty(&file)?
+        ))
+    }
+}
+
+/// The part of the config that `gcmt init` writes.
+#[derive(Serialize)]
+struct InitFile {
+    case_strategy: CaseStrategy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    case_strategies: Option<InitCaseStrategies>,
+    limits: InitLimits,
+    types: Vec<CommitType>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    scopes: Vec<Scope>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    template: Option<InitTemplate>,
+}
+
+#[derive(Serialize)]
+struct InitCaseStrategies {
+    subject: CaseStrategy,
+}
+
+#[derive(Serialize)]
+struct InitTemplate {
+    header: &'static str,
+}
+
+#[derive(Serialize)]
+struct InitLimits {
+    header: usize,
+    body_line: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{
+        commit::{
+            constants::DEFAULT_HEADER_TEMPLATE, strategy::CaseStrategy,
+            template::MessageTemplate,
+        },
+        config::{
+            config::Config, layer::ConfigSource, preset::Preset,
+            validate::validate_file,
+        },
+    };
+
+    use super::InitConfig;
+
+    #[test]
+    fn renders_valid_configs() {
+        for preset in Preset::ALL {
+            let init = InitConfig {
+                preset,
+                case_strategy: CaseStrategy::KebabCase,
+                scopes: vec!["src".to_string(), "my_docs".to_string()],
+                header: 60,
+                body_line: Some(100),
+            };
+            let content = init.render().unwrap();
+            let source = ConfigSource::Repo(PathBuf::from(".gcmt.toml"));
+            assert!(validate_file(&content, &source).is_empty());
+            let config = toml::from_str::<Config>(&content).unwrap();
+            assert_eq!(config.types, preset.types());
+            let header = preset.header_template();
+            assert_eq!(
+                config.template,
+                MessageTemplate::new(
+                    header.unwrap_or(DEFAULT_HEADER_TEMPLATE),
+                    Vec::new()
+                )
+                .unwrap()
+            );
+            assert_eq!(config.scopes, init.scopes());
+            assert_eq!(config.scopes[1].name, "my-docs");
+            assert_eq!(config.limits.header, 60);
+            assert_eq!(config.limits.body_line, Some(100));
+            assert_eq!(
+                config.case_strategies.subject,
+                Some(CaseStrategy::Lowercase)
+            );
+        }
+    }
+}
@@ -0,0 +1,514 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use toml::{Table, Value};
+
+use super::{
+    config::Config,
+    error::ConfigError,
+    extends::read_file,
+    policy::{Locks, OnOverride},
+    validate::Diagnostic,
+};
+
+/// The prefix of the environment variables that override config values.
+pub const ENV_PREFIX: &str = "GCMT_";
+
+/// Where a configuration value comes from, from the lowest to the highest
+/// precedence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// The built-in defaults
+    Default,
+    /// The `$EDITOR` environment variable, used as a fallback for `editor`
+    EditorEnv,
+    /// A policy file, which may lock some of its values
+    Policy(PathBuf),
+    /// The user-level config file
+    Global(PathBuf),
+    /// The project-level config file
+    Repo(PathBuf),
+    /// A `GCMT_*` environment variable
+    Env(String),
+    /// A command-line flag
+    Cli(String),
+}
+
+impl ConfigSource {
+    /// The path of the config file, for the sources that are files.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            ConfigSource::Policy(path)
+            | ConfigSource::Global(path)
+            | ConfigSource::Repo(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The rank of the source, from the lowest to the highest precedence.
+    pub fn precedence(&self) -> usize {
+        match self {
+            ConfigSource::Default => 0,
+            ConfigSource::EditorEnv => 1,
+            ConfigSource::Policy(_) => 2,
+            ConfigSource::Global(_) => 3,
+            ConfigSource::Repo(_) => 4,
+            ConfigSource::Env(_) => 5,
+            ConfigSource::Cli(_) => 6,
+        }
+    }
+
+    /// The same kind of source, for another config file.
+    pub fn with_path(&self, path: PathBuf) -> Self {
+        match self {
+            ConfigSource::Global(_) => ConfigSource::Global(path),
+            _ => ConfigSource::Repo(path),
+        }
+    }
+}
+
+impl Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::EditorEnv => write!(f, "env: EDITOR"),
+            ConfigSource::Policy(path) => {
+                write!(f, "policy: {}", path.display())
+            }
+            ConfigSource::Global(path) => {
+                write!(f, "global: {}", path.display())
+            }
+            ConfigSource::Repo(path) => write!(f, "repo: {}", path.display()),
+            ConfigSource::Env(name) => write!(f, "env: {}", name),
+            ConfigSource::Cli(flag) => write!(f, "cli: {}", flag),
+        }
+    }
+}
+
+/// A set of configuration values coming from a single source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigLayer {
+    pub source: ConfigSource,
+    pub table: Table,
+}
+
+impl ConfigLayer {
+    /// The layer holding the built-in defaults.
+    pub fn defaults() -> Self {
+        let table = match Value::try_from(Config::default()) {
+            Ok(Value::Table(table)) => table,
+            _ => Table::new(),
+        };
+        Self {
+            source: ConfigSource::Default,
+            table,
+        }
+    }
+
+    /// Reads a layer from a config file, along with the files it extends.
+    pub fn from_file(
+        path: &Path,
+        source: ConfigSource,
+    ) -> Result<Self, ConfigError> {
+        let table = read_file(path, &source)?;
+        Ok(Self { source, table })
+    }
+
+    /// Creates a layer holding a single value, where `key` may be a dotted
+    /// path such as `limits.header`. The raw value is read as a TOML value
+    /// when possible, and as a plain string otherwise.
+    pub fn from_pair(
+        source: ConfigSource,
+        key: &str,
+        raw: &str,
+    ) -> Result<Self, ConfigError> {
+        Self::from_value(source, key, parse_raw_value(raw))
+    }
+
+    /// Creates a layer holding a single value, where `key` may be a dotted
+    /// path such as `limits.header`.
+    pub fn from_value(
+        source: ConfigSource,
+        key: &str,
+        mut value: Value,
+    ) -> Result<Self, ConfigError> {
+        for part in key.rsplit('.') {
+            let mut table = Table::new();
+            table.insert(part.to_string(), value);
+            value = Value::Table(table);
+        }
+        let table = match value {
+            Value::Table(table) => table,
+            _ => Table::new(),
+        };
+        Config::deserialize(Value::Table(table.clone())).map_err(|error| {
+            // the key is already named by the source, so only the first line
+            // of the message is kept
+            let message = error.to_string();
+            let message = message.lines().next().unwrap_or_default();
+            ConfigError::InvalidValueError(source.clone(), message.to_string())
+        })?;
+        Ok(Self { source, table })
+    }
+
+    /// The layer for the `$EDITOR` environment variable, if it is set.
+    pub fn editor_env() -> Option<Self> {
+        let editor = env::var("EDITOR").ok()?;
+        let mut table = Table::new();
+        table.insert("editor".to_string(), Value::String(editor));
+        Some(Self {
+            source: ConfigSource::EditorEnv,
+            table,
+        })
+    }
+
+    /// The layers for the `GCMT_*` environment variables. Nested keys are
+    /// separated by a double underscore, as in `GCMT_LIMITS__HEADER`.
+    pub fn from_env() -> Result<Vec<Self>, ConfigError> {
+        Self::from_env_vars().into_iter().collect()
+    }
+
+    /// Like `from_env`, but keeps the result of every variable. The
+    /// variables that do not name a config key are left out, see
+    /// `unknown_env_vars`.
+    pub fn from_env_vars() -> Vec<Result<Self, ConfigError>> {
+        env_vars()
+            .into_iter()
+            .filter(|(_, key, _)| is_config_key(key))
+            .map(|(name, key, raw)| {
+                Self::from_pair(ConfigSource::Env(name), &key, &raw)
+            })
+            .collect()
+    }
+
+    /// The `GCMT_*` environment variables that do not name a config key.
+    /// They are ignored, as they may be meant for another tool.
+    pub fn unknown_env_vars() -> Vec<String> {
+        env_vars()
+            .into_iter()
+            .filter(|(_, key, _)| !is_config_key(key))
+            .map(|(name, _, _)| name)
+            .collect()
+    }
+}
+
+/// The `GCMT_*` environment variables, along with the dotted key they set
+/// and their raw value.
+fn env_vars() -> Vec<(String, String, String)> {
+    let mut vars = env::vars()
+        .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+        .map(|(name, raw)| {
+            let key =
+                name[ENV_PREFIX.len()..].to_lowercase().replace("__", ".");
+            (name, key, raw)
+        })
+        .collect::<Vec<(String, String, String)>>();
+    vars.sort();
+    vars
+}
+
+/// Whether the top-level part of a dotted key is a key of the config.
+fn is_config_key(key: &str) -> bool {
+    let top = key.split('.').next().unwrap_or_default();
+    ConfigLayer::defaults().table.contains_key(top)
+}
+
+/// The effective config, along with the source of each of its values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedConfig {
+    pub config: Config,
+    pub values: Table,
+    /// Maps the dotted path of each value to the layer it came from
+    pub origins: BTreeMap<String, ConfigSource>,
+    /// The problems that do not keep the config from loading, such as the
+    /// overrides of locked keys that were ignored
+    pub warnings: Vec<String>,
+}
+
+impl ResolvedConfig {
+    /// Merges the layers key by key, in the order of the precedence of
+    /// their sources, and then in the given order. The keys locked by a
+    /// policy cannot be overridden by the layers that come after the policy:
+    /// depending on the policy, the overriding value is either ignored with
+    /// a warning or rejected.
+    pub fn resolve(
+        mut layers: Vec<ConfigLayer>,
+        locks: &[Locks],
+    ) -> Result<Self, ConfigError> {
+        layers.sort_by_key(|layer| layer.source.precedence());
+        let mut values = Table::new();
+        let mut origins = BTreeMap::new();
+        let mut warnings = Vec::new();
+        let mut active = Vec::<&Locks>::new();
+        for mut layer in layers {
+            for locks in &active {
+                for (key, locked) in &locks.keys {
+                    if lookup(&layer.table, key)
+                        .is_none_or(|value| value == locked)
+                    {
+                        continue;
+                    }
+                    let error = ConfigError::LockedKeyError {
+                        key: key.clone(),
+                        locked_by: locks.source.clone(),
+                        source: layer.source.clone(),
+                    };
+                    match locks.on_override {
+                        OnOverride::Warn => warnings.push(error.to_string()),
+                        OnOverride::Error => return Err(error),
+                    }
+                    remove_path(&mut layer.table, key);
+                }
+            }
+            merge(&mut values, layer.table, &layer.source, &mut origins, "");
+            active.extend(
+                locks.iter().filter(|locks| locks.source == layer.source),
+            );
+        }
+        let config = Config::deserialize(Value::Table(values.clone()))
+            .map_err(|error| ConfigError::MergeError(error.to_string()))?;
+        let problems = config.check();
+        if !problems.is_empty() {
+            // each problem is blamed on the layer that set the value, or on
+            // the closest parent value
+            let diagnostics = problems
+                .into_iter()
+                .map(|(key, message)| {
+                    let source = key
+                        .match_indices('.')
+                        .map(|(i, _)| &key[..i])
+                        .chain([key.as_str()])
+                        .rev()
+                        .find_map(|key| origins.get(key))
+                        .cloned()
+                        .unwrap_or(ConfigSource::Default);
+                    Diagnostic::at_key(&source, &key, message)
+                })
+                .collect();
+            return Err(ConfigError::InvalidConfigError(diagnostics));
+        }
+        let strategy = config.case_strategy;
+        if strategy.joins_words() && config.case_strategies.subject.is_none() {
+            warnings.push(format!(
+                "`case_strategy` is {}, which joins the words, so the \
+                 subject is lowercase; set `case_strategies.subject` to \
+                 choose its case",
+                strategy
+            ));
+        }
+        Ok(Self {
+            config,
+            values,
+            origins,
+            warnings,
+        })
+    }
+}
+
+/// Looks up the value at a dotted path.
+pub fn lookup<'a>(table: &'a Table, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let mut value = table.get(parts.next()?)?;
+    for part in parts {
+        value = value.as_table()?.get(part)?;
+    }
+    Some(value)
+}
+
+/// Removes the value at a dotted path.
+fn remove_path(table: &mut Table, path: &str) -> Option<Value> {
+    match path.split_once('.') {
+        Some((key, rest)) => {
+            remove_path(table.get_mut(key)?.as_table_mut()?, rest)
+        }
+        None => table.remove(path),
+    }
+}
+
+fn merge(
+    base: &mut Table,
+    overlay: Table,
+    source: &ConfigSource,
+    origins: &mut BTreeMap<String, ConfigSource>,
+    prefix: &str,
+) {
+    for (key, value) in overlay {
+        let path = format!("{}{}", prefix, key);
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => {
+                let prefix = format!("{}.", path);
+                merge(base, overlay, source, origins, &prefix);
+            }
+            (_, value) => {
+                let nested = format!("{}.", path);
+                origins.retain(|key, _| !key.starts_with(&nested));
+                if let Value::Table(table) = &value {
+                    record_origins(table, source, origins, &nested);
+                } else {
+                    origins.insert(path, source.clone());
+                }
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn record_origins(
+    table: &Table,
+    source: &ConfigSource,
+    origins: &mut BTreeMap<String, ConfigSource>,
+    prefix: &str,
+) {
+    for (key, value) in table {
+        let path = format!("{}{}", prefix, key);
+        match value {
+            Value::Table(table) => {
+                record_origins(table, source, origins, &format!("{}.", path))
+            }
+            _ => {
+                origins.insert(path, source.clone());
+            }
+        }
+    }
+}
+
+/// Reads a raw value given on the command line or in the environment.
+fn parse_raw_value(raw: &str) -> Value {
+    match toml::from_str::<Table>(&format!("value = {}", raw)) {
+        Ok(mut table) => table
+            .remove("value")
+            .unwrap_or_else(|| Value::String(raw.to_string())),
+        Err(_) => Value::String(raw.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{
+        is_config_key, ConfigError, ConfigLayer, ConfigSource, ResolvedConfig,
+    };
+
+    fn layer(source: &ConfigSource, key: &str, raw: &str) -> ConfigLayer {
+        ConfigLayer::from_pair(source.clone(), key, raw).unwrap()
+    }
+
+    #[test]
+    fn merges_layers_by_precedence() {
+        let global = ConfigSource::Global(PathBuf::from("config.toml"));
+        let repo = ConfigSource::Repo(PathBuf::from(".gcmt.toml"));
+        let env = ConfigSource::Env("GCMT_LIMITS__HEADER".to_string());
+        // the layers are merged by precedence, whatever their order
+        let layers = vec![
+            layer(&env, "limits.header", "50"),
+            layer(&repo, "limits.header", "60"),
+            layer(&repo, "editor", "vim"),
+            layer(&ConfigSource::EditorEnv, "editor", "nano"),
+            layer(&global, "limits.body_line", "100"),
+            layer(&global, "editor", "emacs"),
+            ConfigLayer::defaults(),
+        ];
+        let resolved = ResolvedConfig::resolve(layers, &[]).unwrap();
+        assert_eq!(resolved.config.limits.header, 50);
+        assert_eq!(resolved.origins["limits.header"], env);
+        assert_eq!(resolved.config.limits.body_line, Some(100));
+        assert_eq!(resolved.origins["limits.body_line"], global);
+        assert_eq!(resolved.config.editor, "vim");
+        assert_eq!(resolved.origins["editor"], repo);
+        assert_eq!(resolved.origins["wrap_width"], ConfigSource::Default);
+        // $EDITOR only replaces the built-in editor
+        let layers = vec![
+            layer(&global, "editor", "emacs"),
+            layer(&ConfigSource::EditorEnv, "editor", "nano"),
+            ConfigLayer::defaults(),
+        ];
+        let resolved = ResolvedConfig::resolve(layers, &[]).unwrap();
+        assert_eq!(resolved.config.editor, "emacs");
+        let layers = vec![
+            layer(&ConfigSource::EditorEnv, "editor", "nano"),
+            ConfigLayer::defaults(),
+        ];
+        let resolved = ResolvedConfig::resolve(layers, &[]).unwrap();
+        assert_eq!(resolved.config.editor, "nano");
+        assert_eq!(resolved.origins["editor"], ConfigSource::EditorEnv);
+    }
+
+    #[test]
+    fn merges_tables_key_by_key() {
+        let repo = ConfigSource::Repo(PathBuf::from(".gcmt.toml"));
+        let cli = ConfigSource::Cli("-c".to_string());
+        let layers = vec![
+            ConfigLayer::defaults(),
+            layer(&repo, "case_strategies.scope", "\"kebab-case\""),
+            layer(&cli, "case_strategies", "{ subject = \"uppercase\" }"),
+        ];
+        let resolved = ResolvedConfig::resolve(layers, &[]).unwrap();
+        assert_eq!(resolved.origins["case_strategies.subject"], cli);
+        assert_eq!(resolved.origins["case_strategies.scope"], repo);
+        assert!(is_config_key("limits.header"));
+        assert!(!is_config_key("token"));
+    }
+
+    #[test]
+    fn rejects_scopes_that_break_the_case() {
+        let repo = ConfigSource::Repo(PathBuf::from(".gcmt.toml"));
+        let layers = vec![
+            ConfigLayer::defaults(),
+            layer(&repo, "scopes", "[{ name = \"Parser\" }]"),
+        ];
+        assert!(matches!(
+            ResolvedConfig::resolve(layers, &[]),
+            Err(ConfigError::InvalidConfigError(diagnostics))
+                if diagnostics[0].source == repo
+        ));
+    }
+
+    #[test]
+    fn places_every_problem_of_the_merged_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".gcmt.toml");
+        std::fs::write(
+            &path,
+            "[[types]]\nname = \"fix\"\n\
+             [[types]]\nname = \"fix\"\n\
+             [[footers]]\ntoken = \"Refs\"\nrequired_for = [\"feat\"]\n",
+        )
+        .unwrap();
+        let repo = ConfigSource::Repo(path.clone());
+        let layers = vec![
+            ConfigLayer::defaults(),
+            ConfigLayer::from_file(&path, repo).unwrap(),
+        ];
+        let Err(ConfigError::InvalidConfigError(diagnostics)) =
+            ResolvedConfig::resolve(layers, &[])
+        else {
+            panic!("the config should be rejected");
+        };
+        let positions = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.position)
+            .collect::<Vec<Option<(usize, usize)>>>();
+        assert_eq!(positions, vec![Some((4, 1)), Some((7, 17))]);
+    }
+
+    #[test]
+    fn warns_about_a_joining_case_for_the_subject() {
+        let repo = ConfigSource::Repo(PathBuf::from(".gcmt.toml"));
+        let layers = vec![
+            ConfigLayer::defaults(),
+            layer(&repo, "case_strategy", "\"kebab-case\""),
+        ];
+        let resolved = ResolvedConfig::resolve(layers.clone(), &[]).unwrap();
+        assert_eq!(resolved.warnings.len(), 1);
+        let mut layers = layers;
+        layers.push(layer(&repo, "case_strategies.subject", "\"lowercase\""));
+        let resolved = ResolvedConfig::resolve(layers, &[]).unwrap();
+        assert!(resolved.warnings.is_empty());
+    }
+}
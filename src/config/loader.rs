@@ -0,0 +1,172 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use crate::git::git::Git;
+
+use super::{
+    config::Config,
+    error::ConfigError,
+    layer::{ConfigLayer, ConfigSource, ResolvedConfig},
+    policy::{policy_files, read_policy},
+};
+
+/// The name of the project-level config file.
+pub const CONFIG_FILE_NAME: &str = ".gcmt.toml";
+
+impl Config {
+    /// Loads the config for the current working directory.
+    pub fn load(
+        cli_layers: Vec<ConfigLayer>,
+    ) -> Result<ResolvedConfig, ConfigError> {
+        Self::load_for(&current_dir(), cli_layers)
+    }
+
+    /// Loads the config for the given directory by merging, from the lowest
+    /// to the highest precedence, the built-in defaults, the policy files,
+    /// the global config file, the project config files, the `GCMT_*`
+    /// environment variables and the given command-line layers.
+    pub fn load_for(
+        dir: &Path,
+        cli_layers: Vec<ConfigLayer>,
+    ) -> Result<ResolvedConfig, ConfigError> {
+        let mut layers = vec![ConfigLayer::defaults()];
+        layers.extend(ConfigLayer::editor_env());
+        let mut locks = Vec::new();
+        for path in policy_files(Git::root().as_deref()) {
+            let (layer, policy_locks) = read_policy(&path)?;
+            layers.push(layer);
+            locks.push(policy_locks);
+        }
+        for (path, source) in Self::files_for(dir) {
+            layers.push(ConfigLayer::from_file(&path, source)?);
+        }
+        layers.extend(ConfigLayer::from_env()?);
+        layers.extend(cli_layers);
+        let mut resolved = ResolvedConfig::resolve(layers, &locks)?;
+        for name in ConfigLayer::unknown_env_vars() {
+            resolved.warnings.push(format!(
+                "Ignoring {}, which does not name a config key",
+                name
+            ));
+        }
+        Ok(resolved)
+    }
+
+    /// The config files that apply to the current working directory, from
+    /// the lowest to the highest precedence.
+    pub fn files() -> Vec<(PathBuf, ConfigSource)> {
+        Self::files_for(&current_dir())
+    }
+
+    /// The config files that apply to the given directory, from the lowest
+    /// to the highest precedence: the global config file, and then every
+    /// project config file from the root of the repository down to `dir`.
+    pub fn files_for(dir: &Path) -> Vec<(PathBuf, ConfigSource)> {
+        let mut files = Vec::new();
+        if let Some(path) = global_config_file() {
+            if path.is_file() {
+                files.push((path.clone(), ConfigSource::Global(path)));
+            }
+        }
+        let root = Git::root();
+        for path in find_config_files(dir, root.as_deref()) {
+            files.push((path.clone(), ConfigSource::Repo(path)));
+        }
+        files
+    }
+}
+
+fn current_dir() -> PathBuf {
+    env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
+/// The user-level config file, under `$XDG_CONFIG_HOME/gcmt/config.toml`,
+/// or `~/.config/gcmt/config.toml` when `$XDG_CONFIG_HOME` is not set.
+pub fn global_config_file() -> Option<PathBuf> {
+    let config_home = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_home.join("gcmt").join("config.toml"))
+}
+
+/// Finds the config files by walking up from `start` until `root` is
+/// reached, and returns them from the outermost to the innermost one. When
+/// `root` is `None`, the walk continues up to the file system root.
+pub fn find_config_files(start: &Path, root: Option<&Path>) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in start.ancestors() {
+        let candidate = dir.join(CONFIG_FILE_NAME);
+        if candidate.is_file() {
+            files.push(candidate);
+        }
+        if Some(dir) == root {
+            break;
+        }
+    }
+    files.reverse();
+    files
+}
+
+/// The directory of the innermost config file that applies to `path`,
+/// relative to `root`, or `root` itself when no nested config file applies.
+pub fn config_dir(root: &Path, path: &str) -> PathBuf {
+    let path = root.join(path);
+    path.ancestors()
+        .skip(1)
+        .take_while(|dir| *dir != root && dir.starts_with(root))
+        .find(|dir| dir.join(CONFIG_FILE_NAME).is_file())
+        .unwrap_or(root)
+        .to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{config_dir, find_config_files, CONFIG_FILE_NAME};
+
+    #[test]
+    fn finds_nested_config_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let docs = root.join("docs");
+        fs::create_dir_all(docs.join("guide")).unwrap();
+        fs::write(root.join(CONFIG_FILE_NAME), "").unwrap();
+        fs::write(docs.join(CONFIG_FILE_NAME), "").unwrap();
+        assert_eq!(
+            find_config_files(&docs.join("guide"), Some(&root)),
+            vec![root.join(CONFIG_FILE_NAME), docs.join(CONFIG_FILE_NAME)]
+        );
+        assert_eq!(config_dir(&root, "docs/guide/intro.md"), docs);
+        assert_eq!(config_dir(&root, "docs/.gcmt.toml"), docs);
+        assert_eq!(config_dir(&root, "src/main.rs"), root);
+        assert_eq!(config_dir(&root, "README.md"), root);
+    }
+
+    #[test]
+    fn stops_at_the_repository_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("repo");
+        let src = root.join("src");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(dir.path().join(CONFIG_FILE_NAME), "").unwrap();
+        assert!(find_config_files(&src, Some(&root)).is_empty());
+        fs::write(root.join(CONFIG_FILE_NAME), "").unwrap();
+        assert_eq!(
+            find_config_files(&src, Some(&root)),
+            vec![root.join(CONFIG_FILE_NAME)]
+        );
+        // outside of a repository, the walk goes on to the file system root
+        let files = find_config_files(&src, None);
+        assert_eq!(
+            files[files.len() - 2..],
+            [
+                dir.path().join(CONFIG_FILE_NAME),
+                root.join(CONFIG_FILE_NAME)
+            ]
+        );
+    }
+}
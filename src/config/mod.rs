@@ -0,0 +1,12 @@
+pub mod commitlint;
+#[allow(clippy::module_inception)]
+pub mod config;
+pub mod error;
+pub mod extends;
+pub mod flow;
+pub mod init;
+pub mod layer;
+pub mod loader;
+pub mod policy;
+pub mod preset;
+pub mod validate;
@@ -0,0 +1,196 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use toml::{Table, Value};
+
+use super::{
+    config::Config,
+    error::ConfigError,
+    layer::{ConfigLayer, ConfigSource},
+    validate::validate_policy,
+};
+
+/// The name of the policy file at the root of a repository.
+pub const POLICY_FILE_NAME: &str = ".gcmt-policy.toml";
+/// The system-wide policy file.
+pub const SYSTEM_POLICY_FILE: &str = "/etc/gcmt/policy.toml";
+/// The key choosing what happens when a layer overrides a locked key.
+pub const ON_OVERRIDE_KEY: &str = "on_override";
+
+/// What happens when a layer overrides a locked key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnOverride {
+    /// The overriding value is ignored with a warning
+    #[default]
+    Warn,
+    /// The config is rejected
+    Error,
+}
+
+/// The values that a policy file locks, by dotted path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Locks {
+    pub source: ConfigSource,
+    pub keys: BTreeMap<String, Value>,
+    pub on_override: OnOverride,
+}
+
+/// Reads a policy file. It is a config file in which any value can be
+/// written as `{ value = ..., locked = true }` to keep the layers of higher
+/// precedence from overriding it. Returns the layer of the policy, which
+/// holds every value, along with the locked ones.
+pub fn read_policy(path: &Path) -> Result<(ConfigLayer, Locks), ConfigError> {
+    let content = fs::read_to_string(path).map_err(|error| {
+        ConfigError::ReadError(path.to_path_buf(), error.to_string())
+    })?;
+    let source = ConfigSource::Policy(path.to_path_buf());
+    // a policy is validated as a config file, with the locks unwrapped
+    let diagnostics = validate_policy(&content, &source);
+    if !diagnostics.is_empty() {
+        return Err(ConfigError::InvalidConfigError(diagnostics));
+    }
+    let parse_error =
+        |message: String| ConfigError::ParseError(path.to_path_buf(), message);
+    let mut table = toml::from_str::<Table>(&content)
+        .map_err(|error| parse_error(error.to_string()))?;
+    let on_override = match table.remove(ON_OVERRIDE_KEY) {
+        Some(value) => OnOverride::deserialize(value)
+            .map_err(|error| parse_error(error.to_string()))?,
+        None => OnOverride::default(),
+    };
+    let mut keys = BTreeMap::new();
+    unwrap_locks(&mut table, "", &mut keys).map_err(parse_error)?;
+    // emptying the scopes would allow any scope again
+    if keys.get("allow_custom_scopes") == Some(&Value::Boolean(false)) {
+        if let Some(scopes) = table.get("scopes") {
+            keys.entry("scopes".to_string())
+                .or_insert_with(|| scopes.clone());
+        }
+    }
+    Config::deserialize(Value::Table(table.clone()))
+        .map_err(|error| parse_error(error.to_string()))?;
+    let locks = Locks {
+        source: source.clone(),
+        keys,
+        on_override,
+    };
+    Ok((ConfigLayer { source, table }, locks))
+}
+
+/// The policy files that exist, from the lowest to the highest precedence.
+pub fn policy_files(root: Option<&Path>) -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from(SYSTEM_POLICY_FILE)];
+    files.extend(root.map(|root| root.join(POLICY_FILE_NAME)));
+    files.retain(|path| path.is_file());
+    files
+}
+
+/// Replaces the `{ value = ..., locked = ... }` tables by their value,
+/// recording the locked ones.
+fn unwrap_locks(
+    table: &mut Table,
+    prefix: &str,
+    keys: &mut BTreeMap<String, Value>,
+) -> Result<(), String> {
+    for (key, value) in table.iter_mut() {
+        let path = format!("{}{}", prefix, key);
+        let Value::Table(inner) = value else {
+            continue;
+        };
+        if !inner.contains_key("locked") {
+            unwrap_locks(inner, &format!("{}.", path), keys)?;
+            continue;
+        }
+        let (Some(Value::Boolean(locked)), Some(unwrapped), 2) =
+            (inner.get("locked"), inner.get("value"), inner.len())
+        else {
+            return Err(format!(
+                "`{}` must be written as {{ value = ..., locked = true }}",
+                path
+            ));
+        };
+        if *locked {
+            keys.insert(path, unwrapped.clone());
+        }
+        *value = unwrapped.clone();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::config::{
+        error::ConfigError,
+        layer::{ConfigLayer, ConfigSource, ResolvedConfig},
+    };
+
+    use super::{read_policy, OnOverride};
+
+    #[test]
+    fn keeps_locked_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.toml");
+        fs::write(
+            &path,
+            "allow_custom_scopes = { value = false, locked = true }\n\
+             [limits]\nheader = { value = 50, locked = true }\n\
+             body_line = 80\n",
+        )
+        .unwrap();
+        let (policy, mut locks) = read_policy(&path).unwrap();
+        let layers = || {
+            let source = ConfigSource::Cli("-c".to_string());
+            vec![
+                ConfigLayer::defaults(),
+                policy.clone(),
+                ConfigLayer::from_pair(source.clone(), "limits.header", "100")
+                    .unwrap(),
+                ConfigLayer::from_pair(source, "limits.body_line", "100")
+                    .unwrap(),
+            ]
+        };
+        let resolved =
+            ResolvedConfig::resolve(layers(), &[locks.clone()]).unwrap();
+        assert_eq!(resolved.config.limits.header, 50);
+        assert_eq!(resolved.config.limits.body_line, Some(100));
+        assert_eq!(resolved.warnings.len(), 1);
+        assert_eq!(
+            resolved.origins.get("limits.header"),
+            Some(&ConfigSource::Policy(path.clone()))
+        );
+        locks.on_override = OnOverride::Error;
+        assert!(matches!(
+            ResolvedConfig::resolve(layers(), &[locks]),
+            Err(ConfigError::LockedKeyError { key, .. }) if key == "limits.header"
+        ));
+    }
+
+    #[test]
+    fn locks_the_scopes_along_with_custom_scopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.toml");
+        fs::write(
+            &path,
+            "allow_custom_scopes = { value = false, locked = true }\n\
+             [[scopes]]\nname = \"cli\"\n",
+        )
+        .unwrap();
+        let (policy, locks) = read_policy(&path).unwrap();
+        let source = ConfigSource::Cli("-c".to_string());
+        let layers = vec![
+            ConfigLayer::defaults(),
+            policy,
+            ConfigLayer::from_pair(source, "scopes", "[]").unwrap(),
+        ];
+        let resolved = ResolvedConfig::resolve(layers, &[locks]).unwrap();
+        assert_eq!(resolved.config.scopes.len(), 1);
+        assert_eq!(resolved.warnings.len(), 1);
+    }
+}
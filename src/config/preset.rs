@@ -0,0 +1,181 @@
+use std::fmt::Display;
+
+use crate::commit::cmt_type::{CommitType, SemverImpact};
+
+/// A built-in set of commit types that `gcmt init` can start from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Preset {
+    /// The types of the Conventional Commits, which are the defaults
+    #[default]
+    Conventional,
+    /// The types of the Angular commit message guidelines
+    Angular,
+    /// The conventional types, described by their gitmoji
+    Gitmoji,
+    /// Only features, fixes and chores
+    Minimal,
+}
+
+impl Display for Preset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Preset::Conventional => {
+                write!(
+                    f,
+                    "conventional: The Conventional Commits types (default)"
+                )
+            }
+            Preset::Angular => {
+                write!(f, "angular: The Angular commit message guidelines")
+            }
+            Preset::Gitmoji => {
+                write!(f, "gitmoji: The conventional types, with gitmoji")
+            }
+            Preset::Minimal => write!(f, "minimal: feat, fix and chore"),
+        }
+    }
+}
+
+impl Preset {
+    /// Every preset, in the order in which they are offered.
+    pub const ALL: [Preset; 4] = [
+        Preset::Conventional,
+        Preset::Angular,
+        Preset::Gitmoji,
+        Preset::Minimal,
+    ];
+
+    /// The short name of the preset.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Conventional => "conventional",
+            Preset::Angular => "angular",
+            Preset::Gitmoji => "gitmoji",
+            Preset::Minimal => "minimal",
+        }
+    }
+
+    /// The commit types of the preset, along with their gitmoji.
+    pub fn types(&self) -> Vec<CommitType> {
+        let types = match self {
+            Preset::Conventional => conventional_types(),
+            Preset::Angular => angular_types(),
+            Preset::Gitmoji => gitmoji_types(),
+            Preset::Minimal => minimal_types(),
+        };
+        types
+            .into_iter()
+            .map(|commit_type| match gitmoji(&commit_type.name) {
+                Some((emoji, shortcode)) => {
+                    commit_type.with_gitmoji(emoji, shortcode)
+                }
+                None => commit_type,
+            })
+            .collect()
+    }
+
+    /// The header template of the preset, when it differs from the default
+    /// one.
+    pub fn header_template(&self) -> Option<&'static str> {
+        match self {
+            Preset::Gitmoji => {
+                Some("{emoji }{type}{(scope)}{breaking}: {subject}")
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The gitmoji of the conventional types, and their shortcodes.
+fn gitmoji(name: &str) -> Option<(&'static str, &'static str)> {
+    match name {
+        "feat" => Some(("✨", ":sparkles:")),
+        "fix" => Some(("🐛", ":bug:")),
+        "docs" => Some(("📝", ":memo:")),
+        "style" => Some(("🎨", ":art:")),
+        "refactor" => Some(("♻️", ":recycle:")),
+        "perf" => Some(("⚡️", ":zap:")),
+        "test" => Some(("✅", ":white_check_mark:")),
+        "build" => Some(("📦️", ":package:")),
+        "ci" => Some(("👷", ":construction_worker:")),
+        "chore" => Some(("🔧", ":wrench:")),
+        "revert" => Some(("⏪️", ":rewind:")),
+        _ => None,
+    }
+}
+
+/// The types of the Conventional Commits.
+fn conventional_types() -> Vec<CommitType> {
+    vec![
+        CommitType::new("feat", Some("A new feature"))
+            .with_semver_impact(SemverImpact::Minor),
+        CommitType::new("fix", Some("A bug fix"))
+            .with_semver_impact(SemverImpact::Patch),
+        CommitType::new("docs", Some("Documentation only changes")),
+        CommitType::new("style", Some("Changes that do not affect the meaning of the code (white-space, formatting, missing semi-colons, etc)")),
+        CommitType::new("refactor", Some("A code change that neither fixes a bug nor adds a feature")),
+        CommitType::new("perf", Some("A code change that improves performance"))
+            .with_semver_impact(SemverImpact::Patch),
+        CommitType::new("test", Some("Adding missing tests or correcting existing tests")),
+        CommitType::new("build", Some("Changes that affect the build system or external dependencies (example scopes: gulp, broccoli, npm)")),
+        CommitType::new("ci", Some("Changes to our CI configuration files and scripts (example scopes: Travis, Circle, BrowserStack, SauceLabs)")),
+        CommitType::new("chore", Some("Other changes that don't modify src or test files")),
+        CommitType::new("revert", Some("Reverts a previous commit")),
+    ]
+}
+
+/// The types of the Angular commit message guidelines, which dropped
+/// `style` and `chore`.
+fn angular_types() -> Vec<CommitType> {
+    vec![
+        CommitType::new("build", Some("Changes that affect the build system or external dependencies (example scopes: gulp, broccoli, npm)")),
+        CommitType::new("ci", Some("Changes to our CI configuration files and scripts (examples: CircleCi, SauceLabs)")),
+        CommitType::new("docs", Some("Documentation only changes")),
+        CommitType::new("feat", Some("A new feature"))
+            .with_semver_impact(SemverImpact::Minor),
+        CommitType::new("fix", Some("A bug fix"))
+            .with_semver_impact(SemverImpact::Patch),
+        CommitType::new("perf", Some("A code change that improves performance"))
+            .with_semver_impact(SemverImpact::Patch),
+        CommitType::new("refactor", Some("A code change that neither fixes a bug nor adds a feature")),
+        CommitType::new("test", Some("Adding missing tests or correcting existing tests")),
+    ]
+}
+
+/// The conventional types, described the way gitmoji describes them. Their
+/// messages start with the gitmoji.
+fn gitmoji_types() -> Vec<CommitType> {
+    vec![
+        CommitType::new("feat", Some("Introduce new features"))
+            .with_semver_impact(SemverImpact::Minor),
+        CommitType::new("fix", Some("Fix a bug"))
+            .with_semver_impact(SemverImpact::Patch),
+        CommitType::new("docs", Some("Add or update documentation")),
+        CommitType::new(
+            "style",
+            Some("Improve structure / format of the code"),
+        ),
+        CommitType::new("refactor", Some("Refactor code")),
+        CommitType::new("perf", Some("Improve performance"))
+            .with_semver_impact(SemverImpact::Patch),
+        CommitType::new("test", Some("Add, update, or pass tests")),
+        CommitType::new(
+            "build",
+            Some("Add or update compiled files or packages"),
+        ),
+        CommitType::new("ci", Some("Add or update CI build system")),
+        CommitType::new("chore", Some("Add or update configuration files")),
+        CommitType::new("revert", Some("Revert changes")),
+    ]
+}
+
+/// The smallest useful set of types.
+fn minimal_types() -> Vec<CommitType> {
+    vec![
+        CommitType::new("feat", Some("A new feature"))
+            .with_semver_impact(SemverImpact::Minor),
+        CommitType::new("fix", Some("A bug fix"))
+            .with_semver_impact(SemverImpact::Patch),
+        CommitType::new("chore", Some("Any other change")),
+    ]
+}
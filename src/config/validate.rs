@@ -0,0 +1,667 @@
+use std::{fmt::Display, fs, ops::Range, path::Path};
+
+use serde::{
+    de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use toml::{Spanned, Table, Value};
+
+use super::{
+    config::Config,
+    extends::{EXTENDS_KEY, REMOVE_TYPES_KEY},
+    layer::ConfigSource,
+    policy::{OnOverride, ON_OVERRIDE_KEY},
+};
+
+/// A problem found in the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub source: ConfigSource,
+    /// The line and the column of the problem, both starting at 1, when
+    /// the source is a file
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.source.path(), self.position) {
+            (Some(path), Some((line, column))) => write!(
+                f,
+                "{}:{}:{}: {}",
+                path.display(),
+                line,
+                column,
+                self.message
+            ),
+            _ => write!(f, "{}: {}", self.source, self.message),
+        }
+    }
+}
+
+impl Diagnostic {
+    /// A problem with the value of a key, such as `types.2.name`, placed
+    /// where the key is set when the source is a file that sets it.
+    pub fn at_key(source: &ConfigSource, key: &str, message: String) -> Self {
+        let position = source
+            .path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| {
+                let span = locate(&content, key)?;
+                Some(position(&content, span.start))
+            });
+        Self {
+            source: source.clone(),
+            position,
+            message,
+        }
+    }
+}
+
+/// Validates the content of a config file, returning every problem found.
+pub fn validate_file(content: &str, source: &ConfigSource) -> Vec<Diagnostic> {
+    let problems = problems(content, source);
+    diagnostics(content, source, problems)
+}
+
+/// Validates the content of a policy file, which is a config file with an
+/// `on_override` key, in which any value can be written as
+/// `{ value = ..., locked = true }`. The locks are replaced by their values,
+/// and the result is validated as a config file.
+pub fn validate_policy(
+    content: &str,
+    source: &ConfigSource,
+) -> Vec<Diagnostic> {
+    #[derive(Deserialize)]
+    struct Policy {
+        on_override: Option<Spanned<Value>>,
+    }
+
+    let locks = match Locks::find(content) {
+        Ok(locks) => locks,
+        // the syntax errors are reported as in any other file
+        Err(_) => return validate_file(content, source),
+    };
+    let mut problems = Vec::new();
+    let policy = toml::from_str::<Policy>(content).ok();
+    if let Some(on_override) = policy.and_then(|policy| policy.on_override) {
+        if let Err(error) =
+            OnOverride::deserialize(on_override.get_ref().clone())
+        {
+            problems.push((on_override.span(), message(&error)));
+        }
+    }
+    let mut config = content.to_string();
+    if let Some(span) = locate(content, ON_OVERRIDE_KEY) {
+        blank(&mut config, span);
+    }
+    for lock in locks.0 {
+        lock.unwrap(&mut config);
+    }
+    problems.extend(self::problems(&config, source));
+    diagnostics(content, source, problems)
+}
+
+/// The problems of the content of a config file.
+fn problems(content: &str, source: &ConfigSource) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if let Err(error) = toml::from_str::<Table>(content) {
+        problems.push((error.span().unwrap_or(0..0), message(&error)));
+    } else {
+        check_values(content, &mut problems);
+        check_extends(content, source, &mut problems);
+    }
+    problems
+}
+
+/// Places the problems in the content, in order.
+fn diagnostics(
+    content: &str,
+    source: &ConfigSource,
+    mut problems: Vec<Problem>,
+) -> Vec<Diagnostic> {
+    problems.sort_by_key(|(span, _)| span.start);
+    problems.dedup();
+    problems
+        .into_iter()
+        .map(|(span, message)| Diagnostic {
+            source: source.clone(),
+            position: Some(position(content, span.start)),
+            message,
+        })
+        .collect()
+}
+
+type Problem = (Range<usize>, String);
+
+fn message(error: &toml::de::Error) -> String {
+    error.message().trim().replace('\n', ": ")
+}
+
+/// Reports the values that `Config` does not accept. Deserializing stops at
+/// the first problem, so the lines of each problem are blanked out and the
+/// file is read again, until it is accepted.
+fn check_values(content: &str, problems: &mut Vec<Problem>) {
+    let mut content = content.to_string();
+    // the keys that are not part of `Config` are checked by `check_extends`
+    for key in [EXTENDS_KEY, REMOVE_TYPES_KEY] {
+        if let Some(span) = locate(&content, key) {
+            blank(&mut content, span);
+        }
+    }
+    while let Err(error) = toml::from_str::<Config>(&content) {
+        let span = error.span().unwrap_or(0..0);
+        problems.push((span.clone(), message(&error)));
+        if !blank(&mut content, span) {
+            break;
+        }
+    }
+}
+
+/// Replaces the lines of a span with spaces, keeping the offsets of the
+/// rest of the content. When the span starts a table, the whole table is
+/// blanked out, and when it is part of a value that spans several lines,
+/// the lines are blanked out until the content can be parsed. Returns
+/// whether anything was blanked out.
+fn blank(content: &mut String, span: Range<usize>) -> bool {
+    let line_end = |offset: usize| {
+        content[offset..]
+            .find('\n')
+            .map_or(content.len(), |i| offset + i + 1)
+    };
+    let start = content[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let mut end = line_end(span.end.saturating_sub(1).max(span.start));
+    if content[start..].trim_start().starts_with('[') {
+        while end < content.len()
+            && !content[end..].trim_start().starts_with('[')
+        {
+            end = line_end(end);
+        }
+    }
+    loop {
+        let blanked = format!(
+            "{}{}{}",
+            &content[..start],
+            spaces(&content[start..end]),
+            &content[end..]
+        );
+        if blanked.parse::<Table>().is_ok() {
+            let changed = blanked != *content;
+            *content = blanked;
+            return changed;
+        }
+        if end == content.len() {
+            return false;
+        }
+        end = line_end(end);
+    }
+}
+
+/// The text with every character but the line breaks replaced by spaces,
+/// keeping its length in bytes.
+fn spaces(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\n' => "\n".to_string(),
+            c => " ".repeat(c.len_utf8()),
+        })
+        .collect()
+}
+
+/// The `{ value = ..., locked = ... }` tables of a policy file.
+struct Locks(Vec<Lock>);
+
+/// A lock of a policy file, with the span that covers its entries and the
+/// span of its value.
+struct Lock {
+    entries: Range<usize>,
+    value: Range<usize>,
+}
+
+impl Locks {
+    fn find(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+}
+
+impl Lock {
+    /// Replaces the lock by its value in the content of the policy file,
+    /// keeping the offsets of the rest of the content. A lock written as a
+    /// table of its own is left out.
+    fn unwrap(&self, content: &mut String) {
+        let open = content[..self.entries.start]
+            .trim_end()
+            .strip_suffix('{')
+            .map(str::len);
+        let close = content[self.entries.end..]
+            .trim_start_matches(|c: char| c.is_whitespace() || c == ',')
+            .strip_prefix('}')
+            .map(|rest| content.len() - rest.len());
+        let (Some(open), Some(close)) = (open, close) else {
+            let header = content[..self.entries.start]
+                .rfind("\n[")
+                .map_or(0, |i| i + 1);
+            blank(content, header..self.entries.end);
+            return;
+        };
+        *content = format!(
+            "{}{}{}{}{}",
+            &content[..open],
+            spaces(&content[open..self.value.start]),
+            &content[self.value.clone()],
+            spaces(&content[self.value.end..close]),
+            &content[close..]
+        );
+    }
+}
+
+impl<'de> Deserialize<'de> for Locks {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(LocksVisitor)
+    }
+}
+
+/// Finds the locks of a value, and of the values it holds.
+struct LocksVisitor;
+
+impl<'de> Visitor<'de> for LocksVisitor {
+    type Value = Locks;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str("a TOML value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Locks, E> {
+        Ok(Locks(Vec::new()))
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Locks, E> {
+        Ok(Locks(Vec::new()))
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Locks, E> {
+        Ok(Locks(Vec::new()))
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Locks, E> {
+        Ok(Locks(Vec::new()))
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<Locks, E> {
+        Ok(Locks(Vec::new()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<Locks, A::Error> {
+        // the items of arrays cannot be locked
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(Locks(Vec::new()))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> Result<Locks, A::Error> {
+        let mut locks = Vec::new();
+        let mut entries: Option<Range<usize>> = None;
+        let mut value = None;
+        let mut locked = false;
+        while let Some(key) = map.next_key::<Spanned<String>>()? {
+            let name = key.get_ref().as_str();
+            if name != "value" && name != "locked" {
+                locks.extend(map.next_value::<Locks>()?.0);
+                continue;
+            }
+            let entry = map.next_value::<Spanned<IgnoredAny>>()?;
+            let start = entries.as_ref().map_or(key.span().start, |span| {
+                span.start.min(key.span().start)
+            });
+            let end = entries.as_ref().map_or(entry.span().end, |span| {
+                span.end.max(entry.span().end)
+            });
+            entries = Some(start..end);
+            match name {
+                "value" => value = Some(entry.span()),
+                _ => locked = true,
+            }
+        }
+        if let (true, Some(entries), Some(value)) = (locked, entries, value) {
+            locks.push(Lock { entries, value });
+        }
+        Ok(Locks(locks))
+    }
+}
+
+/// Reports the extended files that do not exist, and `remove_types` in a
+/// file that does not extend any other, as there is nothing to remove.
+fn check_extends(
+    content: &str,
+    source: &ConfigSource,
+    problems: &mut Vec<Problem>,
+) {
+    #[derive(Deserialize)]
+    struct Extends {
+        #[serde(default)]
+        extends: Vec<Spanned<String>>,
+        #[serde(default)]
+        remove_types: Vec<String>,
+    }
+
+    let file = match toml::from_str::<Extends>(content) {
+        Ok(file) => file,
+        Err(error) => {
+            problems.push((error.span().unwrap_or(0..0), message(&error)));
+            return;
+        }
+    };
+    if !file.remove_types.is_empty() && file.extends.is_empty() {
+        if let Some(span) = locate(content, REMOVE_TYPES_KEY) {
+            problems.push((
+                span,
+                format!(
+                    "`{}` has no effect without `{}`",
+                    REMOVE_TYPES_KEY, EXTENDS_KEY
+                ),
+            ));
+        }
+    }
+    let Some(dir) = source.path().and_then(Path::parent) else {
+        return;
+    };
+    for path in file.extends {
+        if !dir.join(path.get_ref()).is_file() {
+            problems.push((
+                path.span(),
+                format!("extended file `{}` not found", path.get_ref()),
+            ));
+        }
+    }
+}
+
+/// Finds the span of a key, such as `types.2.name`, where the numbers are
+/// the indices of the items of arrays. The span is the one of the key when
+/// it names a value of a table, and the one of the item otherwise. When the
+/// key cannot be found, the span of its closest parent is returned.
+fn locate(content: &str, key: &str) -> Option<Range<usize>> {
+    let path = key.split('.').collect::<Vec<&str>>();
+    (1..=path.len()).rev().find_map(|len| {
+        Locate(&path[..len])
+            .deserialize(toml::Deserializer::new(content))
+            .ok()
+            .flatten()
+    })
+}
+
+/// Finds the span of the value at the end of a path.
+struct Locate<'a>(&'a [&'a str]);
+
+impl<'de> DeserializeSeed<'de> for Locate<'_> {
+    type Value = Option<Range<usize>>;
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Locate<'_> {
+    type Value = Option<Range<usize>>;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str("a table or an array")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> Result<Self::Value, A::Error> {
+        let Some((name, rest)) = self.0.split_first() else {
+            return Ok(None);
+        };
+        while let Some(key) = map.next_key::<Spanned<String>>()? {
+            if key.get_ref() != name {
+                map.next_value::<IgnoredAny>()?;
+            } else if rest.is_empty() {
+                return Ok(Some(key.span()));
+            } else {
+                return map.next_value_seed(Locate(rest));
+            }
+        }
+        Ok(None)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<Self::Value, A::Error> {
+        let Some((index, rest)) = self.0.split_first() else {
+            return Ok(None);
+        };
+        let Ok(index) = index.parse::<usize>() else {
+            return Ok(None);
+        };
+        for _ in 0..index {
+            if seq.next_element::<IgnoredAny>()?.is_none() {
+                return Ok(None);
+            }
+        }
+        if rest.is_empty() {
+            let item = seq.next_element::<Spanned<IgnoredAny>>()?;
+            Ok(item.map(|item| item.span()))
+        } else {
+            Ok(seq.next_element_seed(Locate(rest))?.flatten())
+        }
+    }
+}
+
+/// The line and the column, both starting at 1, of a byte offset.
+fn position(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    use crate::{
+        commit::{
+            cmt_type::{CommitType, SemverImpact},
+            footer::{FooterField, Pattern},
+            limits::LimitOverrides,
+            scope::Scope,
+            strategy::CaseStrategy,
+            template::MessageTemplate,
+        },
+        config::{
+            config::{CaseOverrides, Config},
+            flow::{FlowDefaults, Step},
+        },
+    };
+
+    use super::{
+        locate, position, validate_file, validate_policy, ConfigSource,
+    };
+
+    fn positions(content: &str) -> Vec<(usize, usize)> {
+        let source = ConfigSource::Repo(PathBuf::from(".gcmt.toml"));
+        validate_file(content, &source)
+            .into_iter()
+            .filter_map(|diagnostic| diagnostic.position)
+            .collect()
+    }
+
+    #[test]
+    fn accepts_valid_config() {
+        let content = "case_strategy = \"kebab-case\"\n\
+                       [[types]]\nname = \"feat\"\n";
+        assert!(positions(content).is_empty());
+    }
+
+    #[test]
+    fn reports_every_problem_in_order() {
+        let content = "case_strategy = \"lowercas\"\n\
+                       unknown = 1\n\
+                       [limits]\nheader = \"x\"\nbody_line = true\n\
+                       [[types]]\nname = \"feat\"\nhiden = true\n\
+                       [[types]]\ndescription = \"no name\"\n\
+                       [[scopes]]\nname = \"cli\"\npaths = [\"[a-\"]\n";
+        assert_eq!(
+            positions(content),
+            vec![(1, 17), (2, 1), (4, 10), (5, 13), (8, 1), (9, 1), (13, 9)]
+        );
+    }
+
+    #[test]
+    fn accepts_every_field() {
+        let mut config = Config {
+            case_strategies: CaseOverrides {
+                commit_type: Some(CaseStrategy::Lowercase),
+                scope: Some(CaseStrategy::KebabCase),
+                subject: Some(CaseStrategy::SentenceCase),
+            },
+            scopes: vec![Scope {
+                name: "cli".to_string(),
+                description: Some("The command line".to_string()),
+                aliases: vec!["command".to_string()],
+                paths: vec!["src/cli/**".to_string()],
+            }],
+            allow_custom_scopes: true,
+            footers: vec![FooterField {
+                token: "Refs".to_string(),
+                description: Some("The issues".to_string()),
+                required: true,
+                required_for: vec!["feat".to_string()],
+                multiple: true,
+                pattern: Some(Pattern::new(r"^#\d+$").unwrap()),
+                values: vec!["#1".to_string()],
+            }],
+            template: MessageTemplate::new(
+                "{type}{(scope)}: {subject}",
+                vec!["Change-Type: {type}".to_string()],
+            )
+            .unwrap(),
+            ..Config::default()
+        };
+        config.types.push(CommitType {
+            name: "deps".to_string(),
+            description: Some("Dependency updates".to_string()),
+            emoji: Some("⬆️".to_string()),
+            shortcode: Some(":arrow_up:".to_string()),
+            requires_scope: true,
+            requires_body: true,
+            allow_breaking: false,
+            semver_impact: SemverImpact::Patch,
+            hidden: true,
+        });
+        config.limits.types = BTreeMap::from([(
+            "docs".to_string(),
+            LimitOverrides {
+                header: Some(100),
+                body_line: Some(120),
+                footer_line: Some(120),
+            },
+        )]);
+        config.flow.steps.push(Step::Footer("Refs".to_string()));
+        config.flow.defaults = FlowDefaults {
+            breaking_change: Some(false),
+            push: Some(true),
+            scope: Some("cli".to_string()),
+        };
+        config.flow.prompts =
+            BTreeMap::from([("subject".to_string(), "Why?".to_string())]);
+        let content = toml::to_string(&config).unwrap();
+        assert!(positions(&content).is_empty(), "{}", content);
+        assert_eq!(toml::from_str::<Config>(&content).unwrap(), config);
+        let content = "extends = [\"base.toml\"]\n\
+                       remove_types = [\"chore\"]\n\
+                       [[types]]\nname = \"fix\"\n";
+        // only the extended file is missing
+        assert_eq!(positions(content), vec![(1, 12)]);
+    }
+
+    #[test]
+    fn reports_problems_in_implied_tables() {
+        let content = "[limits.types.docs]\nheader = \"x\"\n\
+                       [flow]\ndefaults.pus = true\n\
+                       [[footers]]\ntoken = \"Refs\"\npattern = \"[a-\"\n";
+        assert_eq!(positions(content), vec![(2, 10), (4, 10), (7, 11)]);
+    }
+
+    #[test]
+    fn reports_problems_in_values_on_several_lines() {
+        let content =
+            "types = [\n  { name = \"fix\" },\n  { nam = \"feat\" },\n]\n\
+                       footers = [{ token = \"Reviewed by\" }]\n";
+        assert_eq!(positions(content), vec![(3, 5), (5, 22)]);
+    }
+
+    #[test]
+    fn reports_template_problems() {
+        let content = "[template]\nheader = \"{type}: {title}\"\n\
+                       trailers = [\"Refs: {type}\", \"{type}\"]\n";
+        assert_eq!(positions(content), vec![(2, 10), (3, 12)]);
+    }
+
+    #[test]
+    fn reports_remove_types_without_extends() {
+        let content = "case_strategy = \"lowercase\"\n\
+                       remove_types = [\"chore\"]\n";
+        assert_eq!(positions(content), vec![(2, 1)]);
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        assert_eq!(positions("types = [\n").len(), 1);
+    }
+
+    #[test]
+    fn reports_problems_in_policies() {
+        let content = "on_override = \"sometimes\"\n\
+                       allow_custom_scopes = { value = false, locked = true }\n\
+                       editor = { locked = true, value = 3 }\n\
+                       [limits]\nheder = { value = 72, locked = true }\n\
+                       body_line = 80\n\
+                       [case_strategies.subject]\nvalue = \"lowercase\"\n\
+                       locked = true\n\
+                       [flow]\ndefaults.push = { value = true, locked = true }\n";
+        let source = ConfigSource::Policy(PathBuf::from("policy.toml"));
+        let positions = validate_policy(content, &source)
+            .into_iter()
+            .filter_map(|diagnostic| diagnostic.position)
+            .collect::<Vec<(usize, usize)>>();
+        assert_eq!(positions, vec![(1, 15), (3, 35), (5, 1)]);
+    }
+
+    #[test]
+    fn locates_keys() {
+        let content = "[[types]]\nname = \"fix\"\n\
+                       [[types]]\nname = \"feat\"\n\
+                       [flow]\nsteps = [\"type\", \"subject\"]\n\
+                       [limits.types.docs]\nheader = 100\n";
+        let at = |key: &str| {
+            locate(content, key).map(|span| position(content, span.start))
+        };
+        assert_eq!(at("types.1.name"), Some((4, 1)));
+        assert_eq!(at("flow.steps.1"), Some((6, 18)));
+        assert_eq!(at("limits.types.docs.header"), Some((8, 1)));
+        // the closest parent that is set
+        assert_eq!(at("types.1.shortcode"), Some((3, 1)));
+        assert_eq!(at("editor"), None);
+    }
+}
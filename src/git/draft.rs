@@ -0,0 +1,103 @@
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::commit::data::CommitData;
+
+use super::git::Git;
+
+/// The path of the draft, relative to the git directory.
+pub const DRAFT_PATH: &str = "gcmt/draft.toml";
+
+/// The path of the draft of the current repository, if any.
+pub fn draft_path() -> Option<PathBuf> {
+    Git::dir().map(|dir| dir.join(DRAFT_PATH))
+}
+
+/// Reads the draft left by a session that ended without a commit, if any.
+/// A draft that cannot be read is an error rather than no draft, so that it
+/// is not lost to the next save.
+pub fn load_draft() -> Result<Option<CommitData>, String> {
+    match draft_path() {
+        Some(path) => read_draft(&path),
+        None => Ok(None),
+    }
+}
+
+fn read_draft(path: &Path) -> Result<Option<CommitData>, String> {
+    let error = |error: &dyn Display| {
+        format!("Cannot read the draft at {}: {}", path.display(), error)
+    };
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(error(&e)),
+    };
+    toml::from_str(&content).map(Some).map_err(|e| error(&e))
+}
+
+/// Keeps the answers so far, so that they can be resumed by the next
+/// session. An empty draft is removed instead.
+pub fn save_draft(data: &CommitData) -> Result<(), String> {
+    if *data == CommitData::default() {
+        clear_draft();
+        return Ok(());
+    }
+    let path = draft_path().ok_or("Cannot find the git directory")?;
+    let content = toml::to_string(data).map_err(|error| error.to_string())?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+    }
+    fs::write(&path, content).map_err(|error| error.to_string())
+}
+
+/// Removes the draft, if any.
+pub fn clear_draft() {
+    if let Some(path) = draft_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::commit::{
+        data::CommitData,
+        footer::{Footer, Separator},
+    };
+
+    use super::read_draft;
+
+    #[test]
+    fn keeps_the_footers() {
+        let data = CommitData {
+            commit_type: Some("fix".to_string()),
+            subject: Some("trim the input".to_string()),
+            footers: vec![
+                Footer {
+                    separator: Separator::Hash,
+                    ..Footer::new("Refs", "12")
+                },
+                Footer::new("Reviewed-by", "someone"),
+            ],
+            ..CommitData::default()
+        };
+        let content = toml::to_string(&data).unwrap();
+        assert!(content.contains("[[footers]]"));
+        assert!(content.contains("separator = \"hash\""));
+        assert_eq!(toml::from_str::<CommitData>(&content).unwrap(), data);
+    }
+
+    #[test]
+    fn reports_a_corrupt_draft() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("draft.toml");
+        assert_eq!(read_draft(&path), Ok(None));
+        fs::write(&path, "type = [").unwrap();
+        assert!(read_draft(&path).is_err());
+        assert!(path.exists());
+    }
+}
@@ -1,6 +1,10 @@
-use std::process::Command;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
 
-use crate::commit::commit::Commit;
+use crate::commit::footer::Footer;
 
 use super::git_change::{GitChange, GitChangeStatus};
 
@@ -12,6 +16,34 @@ impl Git {
         Command::new("git")
     }
 
+    /// The top-level directory of the current git repository, if any.
+    pub fn root() -> Option<PathBuf> {
+        let output = Self::new_git_command()
+            .arg("rev-parse")
+            .arg("--show-toplevel")
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let out_str = String::from_utf8(output.stdout).ok()?;
+        Some(PathBuf::from(out_str.trim()))
+    }
+
+    /// The git directory of the current repository, if any, e.g. `.git`.
+    pub fn dir() -> Option<PathBuf> {
+        let output = Self::new_git_command()
+            .arg("rev-parse")
+            .arg("--absolute-git-dir")
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let out_str = String::from_utf8(output.stdout).ok()?;
+        Some(PathBuf::from(out_str.trim()))
+    }
+
     pub fn changes() -> Vec<GitChange> {
         let output = Self::new_git_command()
             .arg("status")
@@ -21,23 +53,47 @@ impl Git {
         let out_str = String::from_utf8(output.stdout).unwrap();
         let mut changes = Vec::new();
         for line in out_str.lines() {
-            let mut chars = line.chars().collect::<Vec<char>>();
-            chars.reverse();
-            let status = match chars.pop() {
-                Some(' ') => GitChangeStatus::Unstaged,
-                _ => GitChangeStatus::Staged,
+            let mut chars = line.chars();
+            let (Some(index), Some(worktree)) = (chars.next(), chars.next())
+            else {
+                continue;
             };
-            chars.pop();
-            chars.reverse();
-            let path = chars.iter().collect::<String>();
+            let status = GitChangeStatus::from_porcelain(index, worktree);
             // strip white space
-            let path = path.trim();
+            let path = chars.as_str().trim();
+            // renamed files are listed as `old -> new`
+            let path = match path.split_once(" -> ") {
+                Some((_, new)) => new,
+                None => path,
+            };
             changes.push(GitChange::new(path.to_string(), status));
         }
         changes.sort_by(|a, b| a.partial_cmp(b).unwrap());
         changes
     }
 
+    /// The files of the repository at `root` that are tracked, or untracked
+    /// but not ignored, relative to `root`.
+    pub fn files(root: &Path) -> Vec<String> {
+        let output = Self::new_git_command()
+            .arg("-C")
+            .arg(root)
+            .arg("ls-files")
+            .arg("--cached")
+            .arg("--others")
+            .arg("--exclude-standard")
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .map(String::from)
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
     pub fn push() -> Result<String, String> {
         let output = Self::new_git_command()
             .arg("push")
@@ -50,11 +106,24 @@ impl Git {
         }
     }
 
-    pub fn commit(cmt: &Commit) -> Result<String, String> {
-        let output = Self::new_git_command()
-            .arg("commit")
+    pub fn commit(message: &str) -> Result<String, String> {
+        Self::run_commit(message, false)
+    }
+
+    /// Replaces the last commit with one with the given message.
+    pub fn amend(message: &str) -> Result<String, String> {
+        Self::run_commit(message, true)
+    }
+
+    fn run_commit(message: &str, amend: bool) -> Result<String, String> {
+        let mut command = Self::new_git_command();
+        command.arg("commit");
+        if amend {
+            command.arg("--amend");
+        }
+        let output = command
             .arg("-m")
-            .arg(&cmt.to_string())
+            .arg(message)
             .output()
             .expect("Failed to execute git commit");
         if output.status.success() {
@@ -63,4 +132,64 @@ impl Git {
             Err(String::from_utf8(output.stderr).unwrap())
         }
     }
+
+    /// The message of a commit, if it exists.
+    pub fn message(rev: &str) -> Option<String> {
+        let output = Self::new_git_command()
+            .arg("log")
+            .arg("-1")
+            .arg("--format=%B")
+            .arg(rev)
+            .arg("--")
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8(output.stdout).ok()
+    }
+
+    /// The trailers of a commit message, as `git interpret-trailers` reads
+    /// them, so that the trailers known to git, such as `Signed-off-by`,
+    /// are found even when the rest of the footer does not follow the
+    /// Conventional Commits grammar.
+    pub fn trailers(message: &str) -> Vec<Footer> {
+        let child = Self::new_git_command()
+            .arg("interpret-trailers")
+            .arg("--only-trailers")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn();
+        let Ok(mut child) = child else {
+            return Vec::new();
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            if stdin.write_all(message.as_bytes()).is_err() {
+                return Vec::new();
+            }
+        }
+        let output = match child.wait_with_output() {
+            Ok(output) if output.status.success() => output,
+            _ => return Vec::new(),
+        };
+        let mut trailers = Vec::<Footer>::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            // folded values continue on indented lines
+            match (line.starts_with(char::is_whitespace), trailers.last_mut()) {
+                (true, Some(trailer)) => {
+                    trailer.value.push('\n');
+                    trailer.value.push_str(line);
+                }
+                _ => {
+                    let trailer = Footer::parse(line).or_else(|| {
+                        line.split_once(':').map(|(token, value)| {
+                            Footer::new(token.trim(), value.trim())
+                        })
+                    });
+                    trailers.extend(trailer);
+                }
+            }
+        }
+        trailers
+    }
 }
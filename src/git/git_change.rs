@@ -3,6 +3,8 @@ use std::fmt::Display;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GitChangeStatus {
     Staged,
+    /// Staged, with more changes left in the working tree
+    PartlyStaged,
     Unstaged,
 }
 
@@ -10,6 +12,7 @@ impl Display for GitChangeStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let status_str = match self {
             GitChangeStatus::Staged => "Staged",
+            GitChangeStatus::PartlyStaged => "Partly staged",
             GitChangeStatus::Unstaged => "Unstaged",
         };
         write!(f, "{}", status_str)
@@ -17,12 +20,28 @@ impl Display for GitChangeStatus {
 }
 
 impl GitChangeStatus {
+    /// Reads the two status columns of `git status --porcelain`, the first
+    /// one for the index and the second one for the working tree.
+    pub fn from_porcelain(index: char, worktree: char) -> Self {
+        match (index, worktree) {
+            (' ' | '?' | '!', _) => GitChangeStatus::Unstaged,
+            (_, ' ') => GitChangeStatus::Staged,
+            _ => GitChangeStatus::PartlyStaged,
+        }
+    }
+
     pub fn get_status_code(&self) -> usize {
         match self {
             GitChangeStatus::Staged => 0,
-            GitChangeStatus::Unstaged => 1,
+            GitChangeStatus::PartlyStaged => 1,
+            GitChangeStatus::Unstaged => 2,
         }
     }
+
+    /// Whether the change is part of the next commit, at least in part.
+    pub fn is_staged(&self) -> bool {
+        *self != GitChangeStatus::Unstaged
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -60,13 +79,10 @@ impl GitChange {
             .arg("add")
             .arg(&self.path)
             .output();
-        match output {
-            Ok(output) => {
-                if output.status.success() {
-                    self.status = GitChangeStatus::Staged;
-                }
+        if let Ok(output) = output {
+            if output.status.success() {
+                self.status = GitChangeStatus::Staged;
             }
-            Err(_) => {}
         }
     }
 
@@ -75,17 +91,15 @@ impl GitChange {
             .arg("reset")
             .arg(&self.path)
             .output();
-        match output {
-            Ok(output) => {
-                if output.status.success() {
-                    self.status = GitChangeStatus::Unstaged;
-                }
+        if let Ok(output) = output {
+            if output.status.success() {
+                self.status = GitChangeStatus::Unstaged;
             }
-            Err(_) => {}
         }
     }
 }
 
+#[allow(dead_code)]
 pub trait GitChanges {
     fn has_staged_changes(&self) -> bool;
     fn has_unstaged_changes(&self) -> bool;
@@ -94,8 +108,7 @@ pub trait GitChanges {
 
 impl GitChanges for Vec<GitChange> {
     fn has_staged_changes(&self) -> bool {
-        self.iter()
-            .any(|change| change.status == GitChangeStatus::Staged)
+        self.iter().any(|change| change.status.is_staged())
     }
 
     fn has_unstaged_changes(&self) -> bool {
@@ -107,3 +120,25 @@ impl GitChanges for Vec<GitChange> {
         !self.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::GitChangeStatus;
+
+    #[test]
+    fn reads_both_status_columns() {
+        let status = |code: &str| {
+            let mut chars = code.chars();
+            GitChangeStatus::from_porcelain(
+                chars.next().unwrap(),
+                chars.next().unwrap(),
+            )
+        };
+        assert_eq!(status("M "), GitChangeStatus::Staged);
+        assert_eq!(status("A "), GitChangeStatus::Staged);
+        assert_eq!(status(" M"), GitChangeStatus::Unstaged);
+        assert_eq!(status("??"), GitChangeStatus::Unstaged);
+        assert_eq!(status("MM"), GitChangeStatus::PartlyStaged);
+        assert_eq!(status("AM"), GitChangeStatus::PartlyStaged);
+    }
+}
@@ -1,2 +1,4 @@
+pub mod draft;
+#[allow(clippy::module_inception)]
 pub mod git;
 pub mod git_change;
@@ -1,35 +1,92 @@
-use std::{env, ffi::OsString};
-
-use commit::{cmt_type::CommitType, strategy::CaseStrategy};
-use console::Term;
+use clap::Parser;
+use cli::args::{Cli, Command, ConfigCommand};
+use config::{config::Config, layer::ResolvedConfig};
+use console::{style, Term};
 use dialoguer::theme::ColorfulTheme;
 use ui::git_ui::GitUI;
+mod cli;
 mod commit;
+mod config;
 mod git;
 mod ui;
 
 fn main() {
+    let cli = Cli::parse();
+    match &cli.command {
+        Some(Command::Init { force }) => {
+            std::process::exit(cli::init_cmd::init(*force))
+        }
+        Some(Command::Config {
+            command: ConfigCommand::ImportCommitlint { file, print, force },
+        }) => std::process::exit(cli::config_cmd::import_commitlint(
+            file.as_deref(),
+            *print,
+            *force,
+        )),
+        Some(Command::Config {
+            command: ConfigCommand::Validate,
+        }) => std::process::exit(cli::config_cmd::validate(&cli)),
+        _ => {}
+    }
+    let resolved = match load_config(&cli) {
+        Ok(resolved) => resolved,
+        Err(error) => {
+            eprintln!("{}", style(error).red());
+            std::process::exit(1);
+        }
+    };
+    for warning in &resolved.warnings {
+        eprintln!("{}", style(warning).yellow());
+    }
+    match cli.command {
+        Some(Command::Config {
+            command: ConfigCommand::Show { origin },
+        }) => cli::config_cmd::show(&resolved, origin),
+        Some(Command::Show { rev, json }) => std::process::exit(
+            cli::commit_cmd::show(&rev, json, &resolved.config),
+        ),
+        Some(Command::Commit { from_json }) => {
+            std::process::exit(cli::commit_cmd::commit_from_json(
+                &from_json,
+                &resolved.config,
+                cli.amend,
+            ))
+        }
+        Some(Command::Wrap { file }) => std::process::exit(
+            cli::commit_cmd::wrap(file.as_deref(), &resolved.config),
+        ),
+        Some(_) => {}
+        None => run(&cli, &resolved),
+    }
+}
+
+fn load_config(
+    cli: &Cli,
+) -> Result<ResolvedConfig, config::error::ConfigError> {
+    Config::load(cli.config_layers()?)
+}
+
+fn run(cli: &Cli, resolved: &ResolvedConfig) {
     // prep
     let mut term = Term::stdout();
     let theme = ColorfulTheme::default();
-    let types = vec![
-        CommitType::new("feat", Some("A new feature")),
-        CommitType::new("fix", Some("A bug fix")),
-        CommitType::new("docs", Some("Documentation only changes")),
-        CommitType::new("style", Some("Changes that do not affect the meaning of the code (white-space, formatting, missing semi-colons, etc)")),
-        CommitType::new("refactor", Some("A code change that neither fixes a bug nor adds a feature")),
-        CommitType::new("perf", Some("A code change that improves performance")),
-        CommitType::new("test", Some("Adding missing tests or correcting existing tests")),
-        CommitType::new("build", Some("Changes that affect the build system or external dependencies (example scopes: gulp, broccoli, npm)")),
-        CommitType::new("ci", Some("Changes to our CI configuration files and scripts (example scopes: Travis, Circle, BrowserStack, SauceLabs)")),
-        CommitType::new("chore", Some("Other changes that don't modify src or test files")),
-        CommitType::new("revert", Some("Reverts a previous commit")),
-    ];
-    let strat = CaseStrategy::Lowercase;
-    let editor =
-        env::var_os("EDITOR").unwrap_or_else(|| OsString::from("nvim"));
 
-    let mut ui = GitUI::new(&mut term, theme, &types, strat, editor);
+    let mut ui = GitUI::new(&mut term, theme, &resolved.config);
+    if cli.amend {
+        ui = ui.amend();
+    }
 
-    ui.show();
+    let shown = ui.show(|dir| {
+        let nested = Config::load_for(dir, cli.config_layers()?)?;
+        for warning in &nested.warnings {
+            if !resolved.warnings.contains(warning) {
+                eprintln!("{}", style(warning).yellow());
+            }
+        }
+        Ok(nested.config)
+    });
+    if let Err(error) = shown {
+        eprintln!("{}", style(error).red());
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,29 @@
+use std::{error::Error, fmt::Display};
+
+use crate::config::error::ConfigError;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UIError {
+    ConfigError(ConfigError),
+    PushError(String),
+}
+
+impl Display for UIError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            UIError::ConfigError(error) => write!(f, "{}", error),
+            UIError::PushError(reason) => {
+                write!(f, "Could not push: {}", reason.trim_end())
+            }
+        }
+    }
+}
+
+impl Error for UIError {}
+
+impl From<ConfigError> for UIError {
+    fn from(error: ConfigError) -> Self {
+        UIError::ConfigError(error)
+    }
+}
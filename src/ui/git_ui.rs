@@ -1,6 +1,9 @@
-use std::ffi::OsStr;
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
 
-use console::{style, Term};
+use console::{measure_text_width, style, Key, Term};
 use dialoguer::{
     theme::Theme, Confirm, Editor, FuzzySelect, Input, MultiSelect,
 };
@@ -11,82 +14,243 @@ use crate::{
         cmt_type::CommitType,
         commit::Commit,
         error::{CasedComponent, CommitError},
-        strategy::CaseStrategy,
+        footer::{FooterField, BREAKING_CHANGE_TOKEN},
+    },
+    config::{
+        config::Config,
+        error::ConfigError,
+        flow::{Flow, Step},
+        loader::{config_dir, CONFIG_FILE_NAME},
     },
     git::{
+        draft::{clear_draft, load_draft, save_draft},
         git::Git,
         git_change::{GitChange, GitChangeStatus, GitChanges},
     },
 };
 
+use super::error::UIError;
+
 #[derive(Debug)]
-pub struct GitUI<'a, T: AsRef<OsStr>, K: Theme> {
+pub struct GitUI<'a, K: Theme> {
     term: &'a mut Term,
     theme: K,
-    types: &'a Vec<CommitType>,
+    types: Vec<CommitType>,
     builder: CommitBuilder,
     changes: Vec<GitChange>,
     scope: String,
     subject: String,
     description: String,
-    editor: T,
+    editor: OsString,
+    flow: Flow,
+    amend: bool,
+    /// Whether the draft is left as it is, since it cannot be read
+    keep_draft: bool,
 }
 
-impl<'a, T: AsRef<OsStr>, K: Theme> GitUI<'a, T, K> {
-    pub fn new(
-        term: &'a mut Term,
-        theme: K,
-        types: &'a Vec<CommitType>,
-        strategy: CaseStrategy,
-        editor: T,
-    ) -> Self {
+impl<'a, K: Theme> GitUI<'a, K> {
+    pub fn new(term: &'a mut Term, theme: K, config: &Config) -> Self {
         Self {
             term,
             theme,
-            builder: Commit::builder(strategy),
+            builder: config.builder(),
             changes: Vec::new(),
-            types,
-            editor,
+            types: config.types.clone(),
+            editor: config.editor(),
             scope: String::new(),
             subject: String::new(),
             description: String::new(),
+            flow: config.flow(),
+            amend: false,
+            keep_draft: false,
+        }
+    }
+
+    /// Amends the last commit instead of creating a new one. Its answers
+    /// are offered again, and the trailers that the config does not ask for
+    /// are kept.
+    pub fn amend(mut self) -> Self {
+        self.amend = true;
+        self
+    }
+
+    /// Runs the prompts. Once the changes are staged, and narrowed down to
+    /// a scope, `load` is called with the directory of the config that
+    /// applies to them. Returns the error
+    /// of `load`, or the failure of a push that is not asked for.
+    pub fn show<F>(&'a mut self, load: F) -> Result<(), UIError>
+    where
+        F: Fn(&Path) -> Result<Config, ConfigError>,
+    {
+        self.changes = Git::changes();
+        // without a stage step, the changes that are already staged are
+        // committed, as long as there are any; amending needs no changes
+        let has_stage_step = self.flow.steps.contains(&Step::Stage);
+        let needs_changes = !self.amend && !self.changes.has_staged_changes();
+        if (has_stage_step && !self.changes.is_empty()) || needs_changes {
+            self.ask_stage();
+        }
+        self.ask_config(&load)?;
+        // narrowing the staged changes down to a scope may leave them under
+        // another config, which is only chosen once they are final
+        if self.narrow_changes() {
+            self.ask_config(&load)?;
+        }
+        let mut resumed = false;
+        if self.amend {
+            self.load_last_commit();
+        } else {
+            resumed = self.ask_draft();
+        }
+        let steps = self.flow.steps.clone();
+        // without a scope step, the default scope is used as it is
+        if let Some(scope) = self.flow.defaults.scope.clone() {
+            if !resumed && !steps.contains(&Step::Scope) {
+                self.set_scope(&scope);
+            }
+        }
+        let has_review = steps.contains(&Step::Review);
+        for step in &steps {
+            // a resumed draft goes straight to the review
+            if resumed && !matches!(step, Step::Review | Step::Push) {
+                continue;
+            }
+            // without a review step, the commit is made before pushing
+            if *step == Step::Push && !has_review {
+                self.commit();
+            }
+            self.run_step(step)?;
+        }
+        if !has_review && !steps.contains(&Step::Push) {
+            self.commit();
+        }
+        Ok(())
+    }
+
+    fn run_step(&mut self, step: &Step) -> Result<(), UIError> {
+        match step {
+            Step::Stage => {}
+            Step::Type => self.ask_commit_type(),
+            Step::Scope => match self.flow.defaults.scope.clone() {
+                Some(scope) => self.set_scope(&scope),
+                None => self.ask_scope(),
+            },
+            Step::Subject => self.ask_subject(),
+            Step::Description => self.ask_description(),
+            Step::BreakingChange => match self.flow.defaults.breaking_change {
+                Some(false) => {
+                    self.builder.clear_breaking_change();
+                }
+                Some(true) => match self.builder.breaking_change() {
+                    Ok(_) => self.ask_breaking_change_description(),
+                    Err(error) => self.handle_commit_error(error),
+                },
+                None => self.ask_breaking_change(),
+            },
+            Step::Review => self.ask_review_commit()?,
+            Step::Push => match self.flow.defaults.push {
+                Some(true) => self.push().map_err(UIError::PushError)?,
+                Some(false) => {}
+                None => self.ask_push(),
+            },
+            Step::Footer(token) => self.ask_footer(token),
+        }
+        // the answers are kept after every step, so that they survive an
+        // interrupted session
+        if !matches!(step, Step::Review | Step::Push) {
+            self.save_draft();
+        }
+        Ok(())
+    }
+
+    /// Offers to resume the draft left by a session that ended without a
+    /// commit. Returns whether the draft is committed as it is, after the
+    /// review, rather than asked again.
+    fn ask_draft(&mut self) -> bool {
+        let draft = match load_draft() {
+            Ok(Some(draft)) => draft,
+            Ok(None) => return false,
+            Err(error) => {
+                self.keep_draft = true;
+                self.term
+                    .write_line(
+                        &style(format!("{}; it is kept as it is", error))
+                            .yellow()
+                            .to_string(),
+                    )
+                    .unwrap();
+                return false;
+            }
+        };
+        let preview = CommitBuilder::from(draft.clone()).header();
+        let res = FuzzySelect::with_theme(&self.theme)
+            .with_prompt(format!(
+                "Found an unfinished commit: {}",
+                style(preview).cyan().bold()
+            ))
+            .default(0)
+            .items(&["Resume", "Edit", "Discard"])
+            .interact()
+            .unwrap();
+        if res == 2 {
+            clear_draft();
+            return false;
         }
+        if let Err(error) = self.builder.fill(&draft, &self.types) {
+            self.term
+                .write_line(&format!("{}", style(error).yellow()).to_string())
+                .unwrap();
+        }
+        self.scope = draft.scope.unwrap_or_default();
+        self.subject = draft.subject.unwrap_or_default();
+        self.description = draft.body.unwrap_or_default();
+        res == 0
     }
 
-    pub fn show(&'a mut self) {
-        self.ask_stage();
-        self.ask_commit_type();
-        self.ask_scope();
-        self.ask_subject();
-        self.ask_description();
-        self.ask_breaking_change();
-        self.ask_review_commit();
-        self.ask_push();
+    /// Keeps the answers so far as the draft. Amending keeps none, since
+    /// its answers only make sense for the commit it replaces.
+    fn save_draft(&mut self) {
+        if self.amend || self.keep_draft {
+            return;
+        }
+        if let Err(error) = save_draft(&self.builder.data()) {
+            self.term
+                .write_line(
+                    &format!(
+                        "{}",
+                        style(format!("Cannot save the draft: {}", error))
+                            .yellow()
+                    )
+                    .to_string(),
+                )
+                .unwrap();
+        }
     }
 
     fn ask_stage(&mut self) {
         self.changes = Git::changes();
         let mut checked = vec![false; self.changes.len()];
         for (i, change) in self.changes.iter().enumerate() {
-            if change.status == GitChangeStatus::Staged {
+            if change.status.is_staged() {
                 checked[i] = true;
             }
         }
         let selected =
         MultiSelect::with_theme(&self.theme)
-            .with_prompt("Select changes to stage (press space to select/deselect, a to toggle all, and enter to continue)")
+            .with_prompt(self.flow.prompt(&Step::Stage, "Select changes to stage (press space to select/deselect, a to toggle all, and enter to continue)"))
             .items(&self.changes)
             .defaults(&checked)
             .interact()
             .unwrap();
         for i in 0..self.changes.len() {
-            if selected.contains(&i) {
-                self.changes[i].stage();
-            } else {
+            if !selected.contains(&i) {
                 self.changes[i].unstage();
+            } else if self.changes[i].status != GitChangeStatus::PartlyStaged {
+                // the changes left out of a partly staged file are kept out
+                self.changes[i].stage();
             }
         }
-        if !self.changes.has_staged_changes() {
+        if !self.amend && !self.changes.has_staged_changes() {
             let res = FuzzySelect::with_theme(&self.theme)
                 .with_prompt(
                     "No staged changes found. Do you want to quit or retry?",
@@ -102,14 +266,155 @@ impl<'a, T: AsRef<OsStr>, K: Theme> GitUI<'a, T, K> {
         }
     }
 
+    /// Switches to the config of the staged changes. When they fall under
+    /// several nested config files, explains the conflict and asks which
+    /// config to use.
+    fn ask_config<F>(&mut self, load: F) -> Result<(), ConfigError>
+    where
+        F: Fn(&Path) -> Result<Config, ConfigError>,
+    {
+        let Some(root) = Git::root() else {
+            return Ok(());
+        };
+        let mut dirs = Vec::<(PathBuf, Vec<&str>)>::new();
+        for change in &self.changes {
+            if !change.status.is_staged() {
+                continue;
+            }
+            let dir = config_dir(&root, &change.path);
+            match dirs.iter_mut().find(|(other, _)| *other == dir) {
+                Some((_, paths)) => paths.push(&change.path),
+                None => dirs.push((dir, vec![&change.path])),
+            }
+        }
+        let dir = match dirs.len() {
+            0 => return Ok(()),
+            1 => dirs[0].0.clone(),
+            _ => {
+                let labels = dirs
+                    .iter()
+                    .map(|(dir, _)| config_label(&root, dir))
+                    .collect::<Vec<String>>();
+                let mut lines =
+                    vec!["The staged changes fall under different configs:"
+                        .to_string()];
+                for ((_, paths), label) in dirs.iter().zip(&labels) {
+                    lines.push(format!("  {}: {}", label, paths.join(", ")));
+                }
+                self.term
+                    .write_line(&style(lines.join("\n")).yellow().to_string())
+                    .unwrap();
+                let selected = FuzzySelect::with_theme(&self.theme)
+                    .with_prompt("Which config should be used?")
+                    .default(0)
+                    .items(&labels)
+                    .interact()
+                    .unwrap();
+                dirs[selected].0.clone()
+            }
+        };
+        let config = load(&dir)?;
+        self.types = config.types.clone();
+        self.builder = config.builder();
+        self.editor = config.editor();
+        self.flow = config.flow();
+        Ok(())
+    }
+
+    /// Starts from the answers of the last commit, for amending it. The
+    /// answers that the config no longer accepts are reported and left out.
+    fn load_last_commit(&mut self) {
+        let Some(message) = Git::message("HEAD") else {
+            return;
+        };
+        let mut errors = Vec::<CommitError>::new();
+        // a message that does not follow the grammar still keeps the
+        // trailers known to git, such as `Signed-off-by`
+        let footers = match Commit::parse_with(&message, &self.builder.template)
+        {
+            Ok(last) => {
+                let commit_type = self
+                    .types
+                    .iter()
+                    .find(|commit_type| {
+                        commit_type.name == last.commit_type.name
+                    })
+                    .cloned();
+                match commit_type {
+                    Some(commit_type) => errors
+                        .extend(self.builder.commit_type(commit_type).err()),
+                    None => errors.push(CommitError::UnknownCommitTypeError(
+                        last.commit_type.name,
+                    )),
+                }
+                if let Some(scope) = last.scope {
+                    errors.extend(self.builder.scope(&scope).err());
+                    self.scope = scope;
+                }
+                if let Some(description) = last.description {
+                    errors.extend(self.builder.description(&description).err());
+                    self.description = description;
+                }
+                self.subject = last.subject;
+                let explanation = last
+                    .footers
+                    .iter()
+                    .find(|footer| footer.is_breaking_change());
+                if let Some(footer) = explanation {
+                    errors.extend(
+                        self.builder
+                            .breaking_change_description(&footer.value)
+                            .err(),
+                    );
+                } else if last.is_breaking_change {
+                    errors.extend(self.builder.breaking_change().err());
+                }
+                last.footers
+            }
+            Err(_) => Git::trailers(&message),
+        };
+        // the footers of the config are asked again, while the trailers
+        // added by other tools are kept as they are
+        for footer in footers {
+            if footer.is_breaking_change()
+                || self.builder.footer_field(&footer.token).is_some()
+            {
+                continue;
+            }
+            errors.extend(self.builder.add_footer(footer).err());
+        }
+        for error in errors {
+            self.term
+                .write_line(&format!("{}", style(error).yellow()).to_string())
+                .unwrap();
+        }
+    }
+
     fn ask_commit_type(&mut self) {
+        let types = self
+            .types
+            .iter()
+            .filter(|commit_type| !commit_type.hidden)
+            .collect::<Vec<&CommitType>>();
+        let default = self
+            .builder
+            .selected_type()
+            .and_then(|selected| {
+                types
+                    .iter()
+                    .position(|commit_type| commit_type.name == selected.name)
+            })
+            .unwrap_or(0);
         let selected = FuzzySelect::with_theme(&self.theme)
-            .with_prompt("Commit type")
-            .default(0)
-            .items(self.types)
+            .with_prompt(self.flow.prompt(&Step::Type, "Commit type"))
+            .default(default)
+            .items(&types)
             .interact()
             .unwrap();
-        let res = self.builder.commit_type(self.types[selected].clone());
+        let mut commit_type = types[selected].clone();
+        commit_type.name =
+            self.builder.strategies.commit_type.apply(&commit_type.name);
+        let res = self.builder.commit_type(commit_type);
         match res {
             Ok(_) => {}
             Err(error) => {
@@ -119,75 +424,340 @@ impl<'a, T: AsRef<OsStr>, K: Theme> GitUI<'a, T, K> {
     }
 
     fn ask_scope(&mut self) {
+        if self.builder.scopes.scopes().is_empty() {
+            self.ask_custom_scope();
+            return;
+        }
+        // when a scope is required, the "no scope" entry is left out
+        let offset = if self.scope_is_required() { 0 } else { 1 };
+        let detected = self.detect_scope();
+        let default = detected
+            .and_then(|name| {
+                self.builder
+                    .scopes
+                    .scopes()
+                    .iter()
+                    .position(|s| s.name == name)
+            })
+            .map(|i| i + offset)
+            .unwrap_or(0);
+        let mut items = Vec::new();
+        if offset == 1 {
+            items.push("(no scope)".to_string());
+        }
+        items
+            .extend(self.builder.scopes.scopes().iter().map(|s| s.to_string()));
+        if self.builder.scopes.allow_custom() {
+            items.push("(custom scope)".to_string());
+        }
+        let selected = FuzzySelect::with_theme(&self.theme)
+            .with_prompt(self.flow.prompt(&Step::Scope, "Scope"))
+            .default(default)
+            .items(&items)
+            .interact()
+            .unwrap();
+        if selected < offset {
+            self.scope.clear();
+            self.builder.clear_scope();
+            return;
+        }
+        let Some(scope) = self.builder.scopes.scopes().get(selected - offset)
+        else {
+            self.ask_custom_scope();
+            return;
+        };
+        let scope = scope.name.clone();
+        self.set_scope(&scope);
+    }
+
+    fn set_scope(&mut self, scope: &str) {
+        self.scope.clear();
+        self.scope.push_str(scope);
+        let tmp = self.builder.scope(&self.scope);
+        match tmp {
+            Ok(_) => {}
+            Err(error) => {
+                self.handle_commit_error(error);
+            }
+        }
+    }
+
+    /// The scopes that the paths of the staged changes belong to, with
+    /// `None` for the changes that belong to no scope.
+    fn staged_scopes(&self) -> Vec<Option<String>> {
+        self.changes
+            .iter()
+            .filter(|change| change.status.is_staged())
+            .map(|change| {
+                self.builder
+                    .scopes
+                    .detect(&change.path)
+                    .map(|scope| scope.name.clone())
+            })
+            .collect()
+    }
+
+    /// Detects the scope from the paths of the staged changes, when they
+    /// all belong to the same one.
+    fn detect_scope(&self) -> Option<String> {
+        let owners = self.staged_scopes();
+        let first = owners.first()?.clone()?;
+        owners
+            .iter()
+            .all(|owner| owner.as_ref() == Some(&first))
+            .then_some(first)
+    }
+
+    /// When the scope is asked for and the staged changes belong to several
+    /// scopes, offers to narrow them down to the ones of a single scope.
+    /// Returns whether the staged changes were narrowed.
+    fn narrow_changes(&mut self) -> bool {
+        let asks_scope = self.flow.steps.contains(&Step::Scope)
+            && self.flow.defaults.scope.is_none()
+            && !self.builder.scopes.scopes().is_empty();
+        if !asks_scope {
+            return false;
+        }
+        let mut detected = self
+            .staged_scopes()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        detected.sort();
+        detected.dedup();
+        if detected.len() < 2 {
+            return false;
+        }
+        self.term
+            .write_line(
+                &style(format!(
+                    "The staged changes span several scopes: {}",
+                    detected.join(", ")
+                ))
+                .yellow()
+                .to_string(),
+            )
+            .unwrap();
+        let mut items = vec!["Keep all staged changes".to_string()];
+        items.extend(
+            detected
+                .iter()
+                .map(|scope| format!("Only keep the changes in {}", scope)),
+        );
+        let selected = FuzzySelect::with_theme(&self.theme)
+            .with_prompt("Narrow the staged changes?")
+            .default(0)
+            .items(&items)
+            .interact()
+            .unwrap();
+        if selected == 0 {
+            return false;
+        }
+        let scope = detected.swap_remove(selected - 1);
+        for change in self.changes.iter_mut() {
+            let owner = self.builder.scopes.detect(&change.path);
+            if change.status.is_staged()
+                && owner.map(|s| &s.name) != Some(&scope)
+            {
+                change.unstage();
+            }
+        }
+        true
+    }
+
+    fn ask_custom_scope(&mut self) {
         let res = Input::<String>::with_theme(&self.theme)
-            .with_prompt("Scope")
-            .allow_empty(true)
+            .with_prompt(self.flow.prompt(&Step::Scope, "Scope"))
+            .allow_empty(!self.scope_is_required())
             .interact();
-        match res {
-            Ok(scp) => {
-                if !scp.is_empty() {
-                    self.scope.clear();
-                    self.scope.push_str(&self.builder.strategy.apply(&scp));
-                    let tmp = self.builder.scope(&self.scope);
-                    match tmp {
-                        Ok(_) => {}
-                        Err(error) => {
-                            self.handle_commit_error(error);
-                        }
+        if let Ok(scp) = res {
+            if !scp.is_empty() {
+                self.scope.clear();
+                self.scope
+                    .push_str(&self.builder.strategies.scope.apply(&scp));
+                let tmp = self.builder.scope(&self.scope);
+                match tmp {
+                    Ok(_) => {}
+                    Err(error) => {
+                        self.handle_commit_error(error);
                     }
                 }
             }
-            Err(_) => {}
         }
     }
 
     fn ask_subject(&mut self) {
-        let res = Input::<String>::with_theme(&self.theme)
-            .with_prompt("Subject")
-            .interact()
-            .unwrap();
-        if !res.is_empty() {
-            self.subject.clear();
-            self.subject.push_str(&self.builder.strategy.apply(&res));
-            let tmp = self.builder.subject(&self.subject);
-            match tmp {
-                Ok(_) => {}
-                Err(error) => {
-                    self.handle_commit_error(error);
+        let prompt = self.flow.prompt(&Step::Subject, "Subject");
+        let res = if self.term.is_term() {
+            self.read_subject(&prompt)
+        } else {
+            Input::<String>::with_theme(&self.theme)
+                .with_prompt(&prompt)
+                .with_initial_text(&self.subject)
+                .interact()
+        };
+        if let Ok(res) = res {
+            if !res.is_empty() {
+                self.subject.clear();
+                self.subject
+                    .push_str(&self.builder.strategies.subject.apply(&res));
+                let tmp = self.builder.subject(&self.subject);
+                match tmp {
+                    Ok(_) => {}
+                    Err(error) => {
+                        self.handle_commit_error(error);
+                    }
                 }
             }
         }
     }
 
+    /// Reads the subject key by key, showing how many characters are left
+    /// before the header reaches its limit. A subject longer than the
+    /// terminal is redrawn on every row it wraps to.
+    fn read_subject(&mut self, prompt: &str) -> std::io::Result<String> {
+        let mut chars = self.subject.chars().collect::<Vec<char>>();
+        let mut cursor = chars.len();
+        // the row of the cursor, from the first row of the prompt
+        let mut row = 0;
+        loop {
+            let text = chars.iter().collect::<String>();
+            let subject = self.builder.strategies.subject.apply(&text);
+            let left = self.builder.header_limit() as isize
+                - self.builder.header_len(&subject) as isize;
+            let counter = format!("({} left)", left);
+            let counter = if left < 0 {
+                style(counter).red()
+            } else {
+                style(counter).dim()
+            };
+            let mut line = String::new();
+            self.theme
+                .format_input_prompt(
+                    &mut line,
+                    &format!("{} {}", prompt, counter),
+                    None,
+                )
+                .map_err(std::io::Error::other)?;
+            let before_cursor = format!(
+                "{}{}",
+                line,
+                chars[..cursor].iter().collect::<String>()
+            );
+            line.push_str(&text);
+            self.term.move_cursor_up(row)?;
+            self.term.clear_to_end_of_screen()?;
+            self.term.write_str(&line)?;
+            let columns = usize::from(self.term.size().1).max(1);
+            let width = measure_text_width(&line);
+            // a full last row leaves the cursor on it rather than on the
+            // next one
+            if width > 0 && width % columns == 0 {
+                self.term.write_str("\n")?;
+            }
+            let end = width / columns;
+            let position = measure_text_width(&before_cursor);
+            row = position / columns;
+            self.term.move_cursor_up(end - row)?;
+            self.term.write_str("\r")?;
+            self.term.move_cursor_right(position % columns)?;
+            match self.term.read_key()? {
+                Key::Enter => break,
+                Key::Backspace if cursor > 0 => {
+                    cursor -= 1;
+                    chars.remove(cursor);
+                }
+                Key::Del if cursor < chars.len() => {
+                    chars.remove(cursor);
+                }
+                Key::ArrowLeft if cursor > 0 => cursor -= 1,
+                Key::ArrowRight if cursor < chars.len() => cursor += 1,
+                Key::Home => cursor = 0,
+                Key::End => cursor = chars.len(),
+                Key::Char(c) if !c.is_control() => {
+                    chars.insert(cursor, c);
+                    cursor += 1;
+                }
+                _ => {}
+            }
+        }
+        let text = chars.iter().collect::<String>();
+        let mut line = String::new();
+        self.theme
+            .format_input_prompt_selection(&mut line, prompt, &text)
+            .map_err(std::io::Error::other)?;
+        self.term.move_cursor_up(row)?;
+        self.term.clear_to_end_of_screen()?;
+        self.term.write_line(&line)?;
+        Ok(text)
+    }
+
+    fn scope_is_required(&self) -> bool {
+        self.builder
+            .selected_type()
+            .is_some_and(|commit_type| commit_type.requires_scope)
+    }
+
     fn ask_description(&mut self) {
+        let body_is_required = self
+            .builder
+            .selected_type()
+            .is_some_and(|commit_type| commit_type.requires_body);
+        if body_is_required {
+            self.edit_description();
+            return;
+        }
         let should_add_description = Confirm::with_theme(&self.theme)
-            .with_prompt("Add a description?")
+            .with_prompt(
+                self.flow.prompt(&Step::Description, "Add a description?"),
+            )
             .interact()
             .unwrap();
         if should_add_description {
-            let res = Editor::new().executable(&self.editor).edit("").unwrap();
-            match res {
-                Some(description) => {
-                    self.description.clear();
-                    self.description.push_str(&description);
-                    self.builder.description(&self.description);
+            self.edit_description();
+        }
+    }
+
+    fn edit_description(&mut self) {
+        let res = Editor::new()
+            .executable(&self.editor)
+            .edit(&self.description)
+            .unwrap();
+        if let Some(description) = res {
+            self.description.clear();
+            self.description.push_str(&description);
+            let tmp = self.builder.description(&self.description);
+            match tmp {
+                Ok(_) => {}
+                Err(error) => {
+                    self.handle_commit_error(error);
                 }
-                None => {}
             }
         }
     }
 
     fn ask_breaking_change(&mut self) {
+        let breaking_is_allowed = self
+            .builder
+            .selected_type()
+            .is_none_or(|commit_type| commit_type.allow_breaking);
+        if !breaking_is_allowed {
+            self.builder.clear_breaking_change();
+            return;
+        }
         let change_is_safe = dialoguer::Confirm::with_theme(&self.theme)
-        .with_prompt(
+        .with_prompt(self.flow.prompt(
+            &Step::BreakingChange,
             "Is this a safe change? (answer no if this is a breaking change)",
-        )
+        ))
         .interact()
         .unwrap();
-        if !change_is_safe {
+        if change_is_safe {
+            self.builder.clear_breaking_change();
+        } else {
             let res = self.builder.breaking_change();
             match res {
-                Ok(_) => {}
+                Ok(_) => self.ask_breaking_change_description(),
                 Err(error) => {
                     self.handle_commit_error(error);
                 }
@@ -195,6 +765,39 @@ impl<'a, T: AsRef<OsStr>, K: Theme> GitUI<'a, T, K> {
         }
     }
 
+    /// Asks what broke and how to migrate, when the config wants breaking
+    /// changes explained. An empty answer opens the editor instead.
+    fn ask_breaking_change_description(&mut self) {
+        if !self.builder.breaking_change_style.has_footer() {
+            return;
+        }
+        let res = Input::<String>::with_theme(&self.theme)
+            .with_prompt(
+                "What broke, and how to migrate? (leave empty to use the editor)",
+            )
+            .allow_empty(true)
+            .interact_text()
+            .unwrap();
+        let description = match res.trim() {
+            "" => Editor::new()
+                .executable(&self.editor)
+                .edit("")
+                .unwrap()
+                .unwrap_or_default(),
+            res => res.to_string(),
+        };
+        let description = description.trim();
+        if description.is_empty() {
+            self.ask_breaking_change_description();
+            return;
+        }
+        if let Err(error) =
+            self.builder.breaking_change_description(description)
+        {
+            self.handle_commit_error(error);
+        }
+    }
+
     fn get_commit(&mut self) -> Commit {
         let res = self.builder.build();
         match res {
@@ -209,19 +812,60 @@ impl<'a, T: AsRef<OsStr>, K: Theme> GitUI<'a, T, K> {
     fn handle_commit_error(&mut self, error: CommitError) {
         self.term.write_line(&format!("{}", error)).unwrap();
         match error {
-            CommitError::SubjectTooLongError {
-                available: _,
-                actual: _,
-            } => {
+            CommitError::SubjectTooLongError { .. } => {
                 self.ask_subject();
             }
-            CommitError::MissingCommitTypeError => {
+            CommitError::BodyLineTooLongError { .. }
+            | CommitError::FooterLineTooLongError { .. } => {
+                self.edit_description();
+            }
+            CommitError::MissingCommitTypeError
+            | CommitError::UnknownCommitTypeError(_) => {
                 self.ask_commit_type();
             }
             CommitError::MissingSubjectError => {
                 self.ask_subject();
             }
+            CommitError::MissingScopeError(_) => {
+                self.ask_scope();
+            }
+            CommitError::MissingBodyError(_) => {
+                self.edit_description();
+            }
+            CommitError::BreakingChangeNotAllowedError(_) => {
+                self.ask_breaking_change();
+            }
+            CommitError::MissingBreakingChangeError => {
+                self.ask_breaking_change_description();
+            }
+            CommitError::FooterTooLongError { token, .. }
+                if token == BREAKING_CHANGE_TOKEN =>
+            {
+                self.ask_breaking_change_description();
+            }
+            CommitError::UnknownScopeError { .. } => {
+                self.ask_scope();
+            }
+            // the tokens of the config are checked when it is loaded, so
+            // the footer comes from elsewhere and cannot be asked for
+            CommitError::InvalidFooterTokenError(token) => {
+                self.term
+                    .write_line(
+                        &style(format!("The '{}' footer is left out", token))
+                            .yellow()
+                            .to_string(),
+                    )
+                    .unwrap();
+            }
+            CommitError::MissingFooterError(token)
+            | CommitError::InvalidFooterError { token, .. }
+            | CommitError::FooterTooLongError { token, .. } => {
+                self.ask_footer(&token);
+            }
             CommitError::CaseError(component, _, _) => match component {
+                CasedComponent::Type => {
+                    self.ask_commit_type();
+                }
                 CasedComponent::Subject => {
                     self.ask_subject();
                 }
@@ -232,89 +876,46 @@ impl<'a, T: AsRef<OsStr>, K: Theme> GitUI<'a, T, K> {
         }
     }
 
-    fn ask_review_commit(&mut self) {
+    fn ask_review_commit(&mut self) -> Result<(), UIError> {
         let commit = self.get_commit();
-        let revise_options = vec![
-            "Confirm",
-            "Commit Type",
-            "Scope",
-            "Subject",
-            "Description",
-            "Breaking Change",
-            "Quit",
-        ];
+        // only the steps that were asked can be revised
+        let steps = self
+            .flow
+            .steps
+            .iter()
+            .filter(|step| match step {
+                Step::Stage | Step::Review | Step::Push => false,
+                Step::Scope => self.flow.defaults.scope.is_none(),
+                Step::BreakingChange => {
+                    self.flow.defaults.breaking_change.is_none()
+                }
+                _ => true,
+            })
+            .cloned()
+            .collect::<Vec<Step>>();
+        let mut revise_options = vec!["Confirm".to_string()];
+        revise_options.extend(steps.iter().map(|step| match step {
+            Step::Type => "Commit Type".to_string(),
+            Step::Scope => "Scope".to_string(),
+            Step::Subject => "Subject".to_string(),
+            Step::Description => "Description".to_string(),
+            Step::BreakingChange => "Breaking Change".to_string(),
+            step => step.to_string(),
+        }));
+        revise_options.push("Quit".to_string());
         let res = FuzzySelect::with_theme(&self.theme)
             .with_prompt(format!(
-                "Review commit: {}",
-                style(&commit).cyan().bold()
+                "{} ({} release): {}",
+                self.flow.prompt(&Step::Review, "Review commit"),
+                commit.semver_impact(),
+                style(self.builder.template.render(&commit)).cyan().bold()
             ))
             .default(0)
             .items(&revise_options)
             .interact()
             .unwrap();
-        match res {
-            0 => {
-                let res = Git::commit(&commit);
-                match res {
-                    Ok(msg) => {
-                        self.term
-                            .write_line(
-                                &format!("{}", style(msg).green()).to_string(),
-                            )
-                            .unwrap();
-                    }
-                    Err(error) => {
-                        self.term
-                            .write_line(
-                                &format!("{}", style(error).red()).to_string(),
-                            )
-                            .unwrap();
-                        self.ask_review_commit();
-                    }
-                }
-            }
-            1 => {
-                self.ask_commit_type();
-                self.ask_review_commit();
-            }
-            2 => {
-                self.ask_scope();
-                self.ask_review_commit();
-            }
-            3 => {
-                self.ask_subject();
-                self.ask_review_commit();
-            }
-            4 => {
-                self.ask_description();
-                self.ask_review_commit();
-            }
-            5 => {
-                self.ask_breaking_change();
-                self.ask_review_commit();
-            }
-            6 => {
-                let res = Confirm::with_theme(&self.theme)
-                    .with_prompt("Are you sure you want to quit?")
-                    .interact()
-                    .unwrap();
-                if res {
-                    std::process::exit(0);
-                } else {
-                    self.ask_review_commit();
-                }
-            }
-            _ => {}
-        }
-    }
-
-    fn ask_push(&mut self) {
-        let res = Confirm::with_theme(&self.theme)
-            .with_prompt("Do you want to push?")
-            .interact()
-            .unwrap();
-        if res {
-            let res = Git::push();
+        if res == 0 {
+            let res = self.git_commit(&commit);
             match res {
                 Ok(msg) => {
                     self.term
@@ -329,9 +930,171 @@ impl<'a, T: AsRef<OsStr>, K: Theme> GitUI<'a, T, K> {
                             &format!("{}", style(error).red()).to_string(),
                         )
                         .unwrap();
-                    self.ask_push();
+                    return self.ask_review_commit();
                 }
             }
+        } else if let Some(step) = steps.get(res - 1) {
+            self.run_step(step)?;
+            return self.ask_review_commit();
+        } else {
+            let res = Confirm::with_theme(&self.theme)
+                .with_prompt("Are you sure you want to quit?")
+                .interact()
+                .unwrap();
+            if res {
+                self.save_draft();
+                std::process::exit(0);
+            } else {
+                return self.ask_review_commit();
+            }
+        }
+        Ok(())
+    }
+
+    /// Commits without a review, for flows without a review step.
+    fn commit(&mut self) {
+        let commit = self.get_commit();
+        match self.git_commit(&commit) {
+            Ok(msg) => {
+                self.term
+                    .write_line(&format!("{}", style(msg).green()).to_string())
+                    .unwrap();
+            }
+            Err(error) => {
+                self.term
+                    .write_line(&format!("{}", style(error).red()).to_string())
+                    .unwrap();
+                self.save_draft();
+                std::process::exit(1);
+            }
         }
     }
+
+    /// Asks for the value of a footer, or for its values until an empty
+    /// answer when the footer can be given several times.
+    fn ask_footer(&mut self, token: &str) {
+        let field = self
+            .builder
+            .footer_field(token)
+            .cloned()
+            .unwrap_or_default();
+        let required = field.is_required(
+            self.builder
+                .selected_type()
+                .map(|commit_type| commit_type.name.as_str()),
+        );
+        let prompt = self.flow.prompt(
+            &Step::Footer(token.to_string()),
+            field.description.as_deref().unwrap_or(token),
+        );
+        self.builder.clear_footer(token);
+        loop {
+            let is_first = !self.builder.has_footer(token);
+            let value = if field.values.is_empty() {
+                self.ask_footer_text(&field, &prompt, required && is_first)
+            } else {
+                self.ask_footer_value(&field, &prompt, required && is_first)
+            };
+            let Some(value) = value else {
+                return;
+            };
+            if let Err(error) = self.builder.footer(token, value) {
+                self.handle_commit_error(error);
+                return;
+            }
+            if !field.multiple {
+                return;
+            }
+        }
+    }
+
+    fn ask_footer_text(
+        &mut self,
+        field: &FooterField,
+        prompt: &str,
+        required: bool,
+    ) -> Option<String> {
+        let res = Input::<String>::with_theme(&self.theme)
+            .with_prompt(prompt)
+            .allow_empty(!required)
+            .validate_with(|value: &String| {
+                let value = value.trim();
+                if value.is_empty() {
+                    return Ok(());
+                }
+                field.check(value)
+            })
+            .interact_text()
+            .unwrap();
+        let value = res.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    }
+
+    fn ask_footer_value(
+        &mut self,
+        field: &FooterField,
+        prompt: &str,
+        required: bool,
+    ) -> Option<String> {
+        let mut items = field.values.clone();
+        if !required {
+            items.push("(none)".to_string());
+        }
+        let res = FuzzySelect::with_theme(&self.theme)
+            .with_prompt(prompt)
+            .default(0)
+            .items(&items)
+            .interact()
+            .unwrap();
+        field.values.get(res).cloned()
+    }
+
+    fn git_commit(&self, commit: &Commit) -> Result<String, String> {
+        let message = self.builder.template.render(commit);
+        let res = if self.amend {
+            Git::amend(&message)
+        } else {
+            Git::commit(&message)
+        };
+        if res.is_ok() && !self.amend && !self.keep_draft {
+            clear_draft();
+        }
+        res
+    }
+
+    fn ask_push(&mut self) {
+        let res = Confirm::with_theme(&self.theme)
+            .with_prompt(self.flow.prompt(&Step::Push, "Do you want to push?"))
+            .interact()
+            .unwrap();
+        if !res {
+            return;
+        }
+        if let Err(error) = self.push() {
+            self.term
+                .write_line(&format!("{}", style(error).red()).to_string())
+                .unwrap();
+            self.ask_push();
+        }
+    }
+
+    /// Pushes the commits, returning the error of git when it fails.
+    fn push(&mut self) -> Result<(), String> {
+        let msg = Git::push()?;
+        self.term
+            .write_line(&format!("{}", style(msg).green()).to_string())
+            .unwrap();
+        Ok(())
+    }
+}
+
+/// Names the config that applies to a directory, relative to the root of
+/// the repository.
+fn config_label(root: &Path, dir: &Path) -> String {
+    let path = dir.join(CONFIG_FILE_NAME);
+    match path.strip_prefix(root) {
+        _ if !path.is_file() => "the default config".to_string(),
+        Ok(relative) => relative.display().to_string(),
+        Err(_) => path.display().to_string(),
+    }
 }
@@ -0,0 +1,108 @@
+use dialoguer::{theme::Theme, FuzzySelect, Input, MultiSelect};
+
+use crate::{
+    commit::{constants::MAX_MESSAGE_LEN, strategy::CaseStrategy},
+    config::{init::InitConfig, preset::Preset},
+};
+
+/// Asks the questions of `gcmt init`.
+#[derive(Debug)]
+pub struct InitUI<K: Theme> {
+    theme: K,
+    /// The top-level directories of the repository, offered as scopes
+    dirs: Vec<String>,
+}
+
+impl<K: Theme> InitUI<K> {
+    pub fn new(theme: K, dirs: Vec<String>) -> Self {
+        Self { theme, dirs }
+    }
+
+    pub fn show(&self) -> InitConfig {
+        let preset = self.ask_preset();
+        let case_strategy = self.ask_case_strategy();
+        let scopes = self.ask_scopes(case_strategy);
+        let header = self.ask_header_limit();
+        let body_line = self.ask_body_line_limit();
+        InitConfig {
+            preset,
+            case_strategy,
+            scopes,
+            header,
+            body_line,
+        }
+    }
+
+    fn ask_preset(&self) -> Preset {
+        let selected = FuzzySelect::with_theme(&self.theme)
+            .with_prompt("Commit types")
+            .default(0)
+            .items(&Preset::ALL)
+            .interact()
+            .unwrap();
+        Preset::ALL[selected]
+    }
+
+    fn ask_case_strategy(&self) -> CaseStrategy {
+        let items = CaseStrategy::ALL
+            .iter()
+            .map(|strategy| {
+                format!("{}: {}", strategy, strategy.apply("Add API server"))
+            })
+            .collect::<Vec<String>>();
+        let selected = FuzzySelect::with_theme(&self.theme)
+            .with_prompt("Case of the scope and the subject")
+            .default(0)
+            .items(&items)
+            .interact()
+            .unwrap();
+        CaseStrategy::ALL[selected]
+    }
+
+    fn ask_scopes(&self, case_strategy: CaseStrategy) -> Vec<String> {
+        if self.dirs.is_empty() {
+            return Vec::new();
+        }
+        let items = self
+            .dirs
+            .iter()
+            .map(|dir| format!("{} ({}/**)", case_strategy.apply(dir), dir))
+            .collect::<Vec<String>>();
+        let selected = MultiSelect::with_theme(&self.theme)
+            .with_prompt("Scopes, from the top-level directories (press space to select/deselect, a to toggle all, and enter to continue)")
+            .items(&items)
+            .defaults(&vec![true; items.len()])
+            .interact()
+            .unwrap();
+        selected.into_iter().map(|i| self.dirs[i].clone()).collect()
+    }
+
+    fn ask_header_limit(&self) -> usize {
+        Input::<usize>::with_theme(&self.theme)
+            .with_prompt("Maximum length of the header")
+            .default(MAX_MESSAGE_LEN)
+            .validate_with(|limit: &usize| match limit {
+                0 => Err("The limit must be positive"),
+                _ => Ok(()),
+            })
+            .interact_text()
+            .unwrap()
+    }
+
+    fn ask_body_line_limit(&self) -> Option<usize> {
+        let res = Input::<String>::with_theme(&self.theme)
+            .with_prompt("Maximum length of the body lines (empty for none)")
+            .allow_empty(true)
+            .validate_with(|limit: &String| {
+                match limit.trim().parse::<usize>() {
+                    Ok(0) => Err("The limit must be positive"),
+                    Ok(_) => Ok(()),
+                    Err(_) if limit.trim().is_empty() => Ok(()),
+                    Err(_) => Err("The limit must be a number"),
+                }
+            })
+            .interact_text()
+            .unwrap();
+        res.trim().parse().ok()
+    }
+}
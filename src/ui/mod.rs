@@ -1 +1,3 @@
+pub mod error;
 pub mod git_ui;
+pub mod init_ui;